@@ -0,0 +1,64 @@
+//! Generates the snapshot of the github/gitignore templates embedded in the binary when the
+//! `bundled` feature is enabled, from a checkout of the repository at GITNR_BUNDLE_DIR
+
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+const BUNDLE_DIR_ENV: &str = "GITNR_BUNDLE_DIR";
+
+fn main() {
+    println!("cargo:rerun-if-env-changed={BUNDLE_DIR_ENV}");
+    if env::var_os("CARGO_FEATURE_BUNDLED").is_none() {
+        return;
+    }
+
+    let dir = match env::var_os(BUNDLE_DIR_ENV) {
+        Some(dir) => PathBuf::from(dir),
+        None => panic!(
+            "The `bundled` feature requires {BUNDLE_DIR_ENV} to be set to a checkout of https://github.com/github/gitignore"
+        ),
+    };
+    let dir = fs::canonicalize(&dir)
+        .unwrap_or_else(|e| panic!("Failed to read {BUNDLE_DIR_ENV} at {}: {e}", dir.display()));
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    let mut paths: Vec<PathBuf> = Vec::new();
+    collect_templates(&dir, &mut paths);
+    paths.sort();
+
+    let mut source = String::from("const BUNDLED_TEMPLATES: &[(&str, &str)] = &[\n");
+    for path in paths.iter() {
+        let relative = path
+            .strip_prefix(&dir)
+            .unwrap()
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        source.push_str(&format!(
+            "    ({relative:?}, include_str!({:?})),\n",
+            path.display().to_string()
+        ));
+    }
+    source.push_str("];\n");
+
+    let out = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("bundled.rs");
+    fs::write(out, source).expect("Failed to write the bundled templates");
+}
+
+/// Recursively collects the .gitignore template files, skipping hidden files and directories
+fn collect_templates(dir: &Path, paths: &mut Vec<PathBuf>) {
+    let entries = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("Failed to read directory {}: {e}", dir.display()));
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_templates(&path, paths);
+        } else if path.extension().is_some_and(|ext| ext == "gitignore") {
+            paths.push(path);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::template::item::Template;
+use crate::template::detect::DETECT_DEPTH;
 use crate::template::list::TemplateList;
 use anyhow::{bail, Context, Result};
 use clap::{Args, Parser, Subcommand};
@@ -13,17 +13,23 @@ const LONG_ABOUT: &str = r"
 
 Generate a '.gitignore' file using one or more templates from 
 the GitHub & TopTal collections along with your own templates 
-from local files or remote URLs. 
+from the local templates directory, local files, remote URLs or 
+GitHub, GitLab, Gitea & Bitbucket repositories. 
 
-You can also browse the available templates at the GitHub & 
-TopTal collections using the `search` command.";
+You can also browse the available templates of the GitHub, 
+TopTal, local and configured repository collections using the 
+`search` command.";
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = LONG_ABOUT)]
 pub struct Cli {
-    /// Refresh the cache (templates are cached for 1h)
+    /// Refresh the cache (templates are cached for 1h unless configured otherwise)
     #[arg(short = 'r', long = "refresh", global = true)]
     pub refresh: bool,
+    /// Only use cached (or bundled) templates regardless of their age, without any network
+    /// requests. Also used automatically when the network is unreachable.
+    #[arg(short = 'o', long = "offline", global = true)]
+    pub offline: bool,
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -34,8 +40,54 @@ pub struct CommandCreate {
     #[arg(short = 's', long = "save")]
     pub out_gitignore: bool,
     /// Write template to the specified file path
-    #[arg(short = 'f', long = "file")]
+    #[arg(short = 'f', long = "file", conflicts_with = "out_gitignore")]
     pub out_file: Option<String>,
+    /// Merge template into the exclude file of the git repository in the current directory
+    /// (.git/info/exclude), for rules that shouldn't be shared with others
+    #[arg(short = 'e', long = "exclude", conflicts_with_all = ["out_gitignore", "out_file"])]
+    pub out_exclude: bool,
+    /// Merge template into the global excludes file applied to all repositories (the
+    /// core.excludesFile git setting, or ~/.config/git/ignore by default)
+    #[arg(
+        short = 'g',
+        long = "global",
+        conflicts_with_all = ["out_gitignore", "out_file", "out_exclude"]
+    )]
+    pub out_global: bool,
+    /// Merge the output into a managed block in the file being written to, preserving any
+    /// content outside of it (requires an output file)
+    #[arg(short = 'm', long = "merge")]
+    pub merge: bool,
+    /// Use the exact template revisions pinned in the gitnr.lock file of the current directory
+    #[arg(short = 'l', long = "locked")]
+    pub locked: bool,
+    /// Add the templates detected from the files in the current directory (see `detect`)
+    #[arg(short = 'a', long = "auto")]
+    pub auto: bool,
+    /// Don't write anything, print the content that would be written instead
+    #[arg(short = 'n', long = "dry-run")]
+    pub dry_run: bool,
+    /// With --dry-run, evaluate the generated rules against the files in the directory of the
+    /// ignore file and list the files that would become ignored or un-ignored, along with the
+    /// tracked files the rules would match. Only the rules of the ignore file are evaluated,
+    /// not those of the other ignore files of the repository.
+    #[arg(
+        long = "simulate",
+        requires = "dry_run",
+        conflicts_with_all = ["out_exclude", "out_global"]
+    )]
+    pub simulate: bool,
+    /// Print the simulation as JSON
+    #[arg(long = "json", requires = "simulate")]
+    pub json: bool,
+    /// Print a diff between the output file and the content that would be written to it,
+    /// without writing anything [default file: ./.gitignore]
+    #[arg(long = "diff", conflicts_with_all = ["simulate", "confirm"])]
+    pub diff: bool,
+    /// Show a diff of the changes to the output file and ask for confirmation before writing
+    /// it (requires an output file)
+    #[arg(long = "confirm")]
+    pub confirm: bool,
     /// Space or comma separated list of templates to use. Templates can be prefixed with
     /// the provider name to avoid any ambiguity.
     ///
@@ -44,9 +96,13 @@ pub struct CommandCreate {
     ///  - "ghc:"   GitHub community templates
     ///  - "ghg:"   GitHub global templates
     ///  - "tt:"    TopTal templates
+    ///  - "local:" Template from the local templates directory
     ///  - "url:"   Remote URL to text file template
     ///  - "file:"  Local file path to a .gitignore file
     ///  - "repo:"  File from a any public GitHub repo
+    ///  - "gl:"    File from a GitLab repo, as owner/repo/ref/path
+    ///  - "gitea:" File from a Gitea or Forgejo repo, as owner/repo/ref/path
+    ///  - "bb:"    File from a Bitbucket repo, as owner/repo/ref/path
     ///
     /// If no prefix is specified, program will attempt to guess the provider if possible
     /// otherwise it will default to a GitHub template. The template name is case-sensitive.
@@ -61,12 +117,140 @@ pub struct CommandCreate {
     pub templates: Vec<String>,
 }
 
+#[derive(Args, Debug)]
+pub struct CommandSync {
+    /// Path to the manifest file [default: ./.gitnr.toml]
+    #[arg(short = 'p', long = "manifest")]
+    pub manifest: Option<String>,
+    /// Print the output to stdout instead of writing it to the manifest output file
+    #[arg(long = "stdout")]
+    pub stdout: bool,
+    /// Use the exact template revisions pinned in the gitnr.lock file next to the manifest
+    #[arg(short = 'l', long = "locked")]
+    pub locked: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct CommandUpdate {
+    /// Path to the manifest file used when no templates are provided [default: ./.gitnr.toml]
+    #[arg(short = 'p', long = "manifest")]
+    pub manifest: Option<String>,
+    /// Templates to lock, using the same syntax as the create command. If none are provided
+    /// the templates in the project manifest are used.
+    pub templates: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct CommandCheck {
+    /// The ignore file to check [default: ./.gitignore or the manifest output file]
+    #[arg(short = 'f', long = "file")]
+    pub file: Option<String>,
+    /// Path to the manifest file used when no templates are provided [default: ./.gitnr.toml]
+    #[arg(short = 'p', long = "manifest")]
+    pub manifest: Option<String>,
+    /// Use the exact template revisions pinned in the gitnr.lock file
+    #[arg(short = 'l', long = "locked")]
+    pub locked: bool,
+    /// Templates the file is expected to be generated from, using the same syntax as the create
+    /// command. If none are provided the templates in the project manifest are used.
+    pub templates: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct CommandDiff {
+    /// The ignore file to compare against [default: ./.gitignore or the manifest output file]
+    #[arg(short = 'f', long = "file")]
+    pub file: Option<String>,
+    /// Path to the manifest file used when no templates are provided [default: ./.gitnr.toml]
+    #[arg(short = 'p', long = "manifest")]
+    pub manifest: Option<String>,
+    /// Compare against the file with the templates merged into its managed block
+    #[arg(short = 'm', long = "merge")]
+    pub merge: bool,
+    /// Use the exact template revisions pinned in the gitnr.lock file
+    #[arg(short = 'l', long = "locked")]
+    pub locked: bool,
+    /// Templates to generate the content from, using the same syntax as the create command.
+    /// If none are provided the templates in the project manifest are used.
+    pub templates: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct CommandExplain {
+    /// The ignore file to evaluate the paths against [default: ./.gitignore]
+    #[arg(short = 'f', long = "file", conflicts_with = "templates")]
+    pub file: Option<String>,
+    /// Evaluate the paths against the content generated from these templates instead of an
+    /// ignore file, using the same syntax as the create command
+    #[arg(short = 't', long = "template", value_delimiter = ',')]
+    pub templates: Vec<String>,
+    /// Paths to explain, relative to the current directory. Directories can be marked with a
+    /// trailing "/" if they don't exist.
+    #[arg(required = true)]
+    pub paths: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct CommandDetect {
+    /// Maximum directory depth to scan for marker files
+    #[arg(short = 'd', long = "depth", default_value_t = DETECT_DEPTH)]
+    pub depth: usize,
+    /// The directory to scan [default: current directory]
+    pub path: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct CommandCache {
+    #[command(subcommand)]
+    pub command: CacheCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCommand {
+    /// List the cached templates and collections with their age and size
+    List,
+    /// Print the cached content of a template, by template argument or URL
+    Show {
+        /// Template argument (e.g. gh:Rust) or the URL it was fetched from
+        template: String,
+    },
+    /// Clear the whole cache, or only the cache of one provider
+    Clear {
+        /// Provider prefix, e.g. "gh", "tt" or "gl"
+        provider: Option<String>,
+    },
+    /// Remove cached templates and collections older than the given age
+    Prune {
+        /// Age such as 12h, 7d or 2w
+        #[arg(long = "older-than", default_value = "7d")]
+        older_than: String,
+    },
+    /// Print the cache directory
+    Path,
+    /// Print the number and size of the cached templates and collections
+    Stats,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Create a .gitignore file from one or more templates
     Create(CommandCreate),
-    /// Choose templates interactively from the GitHub & TopTal collections
+    /// Generate the ignore file declared in the project manifest (.gitnr.toml)
+    Sync(CommandSync),
+    /// Update the gitnr.lock file to the latest revision of each template
+    Update(CommandUpdate),
+    /// Suggest templates for the project based on the files in it
+    Detect(CommandDetect),
+    /// Check that an ignore file is up to date with its templates, exiting with an error if not
+    Check(CommandCheck),
+    /// Show a diff between an ignore file and the content generated from its templates
+    Diff(CommandDiff),
+    /// Show which rules of an ignore file or templates ignore the given paths
+    Explain(CommandExplain),
+    /// Choose templates interactively from the GitHub, TopTal, local and repository collections
     Search,
+    /// Inspect and manage the template cache
+    Cache(CommandCache),
     /// Generate completions to stdout
     Completions {
         /// Specify desired shell
@@ -79,33 +263,46 @@ impl Cli {
     pub fn templates(&self) -> Result<TemplateList> {
         let templates = match &self.command {
             Some(Commands::Create(args)) => &args.templates,
+            Some(Commands::Detect(_)) => {
+                bail!("Cannot provide template arguments to 'detect' command")
+            }
+            Some(Commands::Check(_)) => {
+                bail!("Cannot provide template arguments to 'check' command")
+            }
+            Some(Commands::Diff(_)) => {
+                bail!("Cannot provide template arguments to 'diff' command")
+            }
+            Some(Commands::Explain(_)) => {
+                bail!("Cannot provide template arguments to 'explain' command")
+            }
+            Some(Commands::Update(_)) => {
+                bail!("Cannot provide template arguments to 'update' command")
+            }
+            Some(Commands::Sync(_)) => {
+                bail!("Cannot provide template arguments to 'sync' command")
+            }
             Some(Commands::Search) => {
                 bail!("Cannot provide template arguments to 'search' command")
             }
+            Some(Commands::Cache(_)) => {
+                bail!("Cannot provide template arguments to 'cache' command")
+            }
             Some(Commands::Completions { shell: _ }) => {
                 bail!("Cannot provide template arguments to 'completions' command")
             }
             None => bail!("Cannot provide template arguments to an unknown command"),
         };
 
-        // Split templates seperated by commas and spaces
-        let templates = templates
-            .iter()
-            .flat_map(|f| f.split(',').map(|s| s.to_string()))
-            .collect::<Vec<String>>();
-
-        // Create the template structs from the templates provided
-        let templates = templates
-            .iter()
-            .map(|name| Template::new(name))
-            .collect::<Result<Vec<Template>>>()
-            .with_context(|| "Failed to parse provided template arguments".to_string())?;
-
-        Ok(TemplateList::new(templates))
+        TemplateList::parse(templates)
+            .with_context(|| "Failed to parse provided template arguments".to_string())
     }
 }
 
-static CLI: Lazy<Cli> = Lazy::new(Cli::parse);
+static CLI: Lazy<Cli> = Lazy::new(|| match cfg!(test) {
+    // The arguments of the test harness aren't gitnr arguments
+    true => Cli::parse_from(["gitnr"]),
+    false => Cli::parse(),
+});
 
 pub fn get_cli() -> &'static Cli {
     &CLI
@@ -0,0 +1,298 @@
+use crate::cli::{CacheCommand, CommandCache};
+use crate::template::item::cache::TemplateCache;
+use crate::template::item::Template;
+use crate::template::provider::{provider, provider_for_url, providers, TemplateProvider};
+use crate::util::fs::cache_dir;
+use crate::util::notes::note;
+use crate::util::string::format_size;
+use crate::util::time::{age, format_duration, parse_duration};
+use anyhow::{bail, Context, Result};
+use indoc::printdoc;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use yansi::Paint;
+
+/// Directory of the cached template collections, relative to the cache directory
+const COLLECTIONS_DIR: &str = "collections";
+
+/// A cached template collection file
+struct CollectionFile {
+    /// Name of the file relative to the cache directory, as used by `cache_filepath`
+    name: String,
+    path: PathBuf,
+    updated: SystemTime,
+    size: u64,
+}
+
+pub fn command(cmd: &CommandCache) -> Result<()> {
+    match &cmd.command {
+        CacheCommand::List => list(),
+        CacheCommand::Show { template } => show(template),
+        CacheCommand::Clear { provider } => clear(provider.as_deref()),
+        CacheCommand::Prune { older_than } => prune(parse_duration(older_than)?),
+        CacheCommand::Path => {
+            println!("{}", cache_dir().display());
+            Ok(())
+        }
+        CacheCommand::Stats => stats(),
+    }
+}
+
+/// Lists the cached templates and collections with their age and size
+fn list() -> Result<()> {
+    let entries = TemplateCache::entries();
+    let collections = collection_files(&cache_dir())?;
+
+    if entries.is_empty() && collections.is_empty() {
+        success("The cache is empty");
+        return Ok(());
+    }
+
+    let rows = entries
+        .iter()
+        .map(|e| {
+            let prefix = provider_for_url(&e.key).map_or("", |p| p.prefix());
+            [
+                format_duration(age(e.updated)),
+                format_size(e.size as u64),
+                prefix.to_string(),
+                e.key.clone(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    print_table(&format!("Templates ({}):", rows.len()), "URL", rows);
+
+    let rows = collections
+        .iter()
+        .map(|c| {
+            let prefixes = providers()
+                .filter(|p| p.cache_files().contains(&c.name))
+                .map(|p| p.prefix())
+                .collect::<Vec<_>>()
+                .join(" ");
+            [
+                format_duration(age(c.updated)),
+                format_size(c.size),
+                prefixes,
+                c.name.clone(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    print_table(&format!("Collections ({}):", rows.len()), "FILE", rows);
+    println!();
+
+    Ok(())
+}
+
+/// Prints the cached content of a template
+fn show(template: &str) -> Result<()> {
+    let url = if TemplateCache::entries().iter().any(|e| e.key == template) {
+        template.to_string()
+    } else {
+        Template::new(template)?.value.url()?
+    };
+    match TemplateCache::get_stale(&url) {
+        Some(content) => println!("{content}"),
+        None => bail!("Template is not in the cache\n{url}"),
+    }
+    Ok(())
+}
+
+/// Clears the whole cache or only the cache of a provider
+fn clear(prefix: Option<&str>) -> Result<()> {
+    let prefix = match prefix {
+        Some(prefix) => prefix,
+        None => {
+            let dir = cache_dir();
+            let dir_str = dir.to_str().unwrap_or("...unknown path");
+            if dir.exists() {
+                fs::remove_dir_all(&dir)
+                    .with_context(|| format!("Failed to remove cache directory\n{dir_str}"))?;
+            }
+            success(&format!("Cleared the cache at path: {dir_str}"));
+            return Ok(());
+        }
+    };
+
+    let provider = provider(prefix)?;
+    let templates = TemplateCache::retain(|e| !provider.owns_url(&e.key))?;
+    let files = clear_collections(&cache_dir(), provider)?;
+
+    // Collection files can hold the collections of several providers, such as the GitHub
+    // tree listing all of the gh:, ghg: and ghc: templates
+    for file in files.iter() {
+        let shared = providers()
+            .filter(|p| p.prefix() != provider.prefix() && p.cache_files().contains(file))
+            .map(|p| p.prefix())
+            .collect::<Vec<_>>();
+        if !shared.is_empty() {
+            note(&format!(
+                "The cached collection file {file} is shared with {}, their collections were cleared as well",
+                shared.join(" ")
+            ));
+        }
+    }
+
+    success(&format!(
+        "Cleared {templates} cached templates and {} collection files of {}",
+        files.len(),
+        provider.title()
+    ));
+    Ok(())
+}
+
+/// Removes the cached templates and collections older than the given age
+fn prune(older_than: Duration) -> Result<()> {
+    let templates = TemplateCache::retain(|e| age(e.updated) <= older_than)?;
+    let files = prune_collections(&cache_dir(), older_than)?;
+
+    success(&format!(
+        "Removed {templates} cached templates and {files} collection files older than {}",
+        format_duration(older_than)
+    ));
+    Ok(())
+}
+
+/// Prints the number and size of the cached templates and collections
+fn stats() -> Result<()> {
+    let entries = TemplateCache::entries();
+    let collections = collection_files(&cache_dir())?;
+
+    let template_size = entries.iter().map(|e| e.size as u64).sum::<u64>();
+    let collection_size = collections.iter().map(|c| c.size).sum::<u64>();
+    let oldest = entries.iter().map(|e| age(e.updated)).max();
+    let newest = entries.iter().map(|e| age(e.updated)).min();
+    let fmt_age = |a: Option<Duration>| a.map_or("-".to_string(), format_duration);
+
+    printdoc! {"
+
+        {title}
+          Directory:    {dir}
+          Templates:    {templates} ({template_size})
+          Oldest:       {oldest}
+          Newest:       {newest}
+          Collections:  {collections} ({collection_size})
+
+        ",
+        title="Cache statistics:".bold(),
+        dir=cache_dir().display(),
+        templates=entries.len(),
+        template_size=format_size(template_size),
+        oldest=fmt_age(oldest),
+        newest=fmt_age(newest),
+        collections=collections.len(),
+        collection_size=format_size(collection_size),
+    }
+    Ok(())
+}
+
+/// Removes the collection files of the provider from the cache directory, returning the names
+/// of the removed files
+pub fn clear_collections(cache_dir: &Path, provider: &dyn TemplateProvider) -> Result<Vec<String>> {
+    let mut removed: Vec<String> = Vec::new();
+    for file in collection_files(cache_dir)? {
+        if provider.cache_files().contains(&file.name) {
+            remove_file(&file)?;
+            removed.push(file.name);
+        }
+    }
+    Ok(removed)
+}
+
+/// Removes the collection files older than the given age from the cache directory, returning
+/// how many were removed
+pub fn prune_collections(cache_dir: &Path, older_than: Duration) -> Result<usize> {
+    let mut removed = 0;
+    for file in collection_files(cache_dir)? {
+        if age(file.updated) > older_than {
+            remove_file(&file)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Lists the template collection files in the cache directory
+fn collection_files(cache_dir: &Path) -> Result<Vec<CollectionFile>> {
+    let dir = cache_dir.join(COLLECTIONS_DIR);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let dir_str = dir.to_str().unwrap_or("...unknown path");
+    let entries =
+        fs::read_dir(&dir).with_context(|| format!("Failed to read cache directory\n{dir_str}"))?;
+
+    let mut files: Vec<CollectionFile> = Vec::new();
+    for entry in entries.flatten() {
+        let metadata = match entry.metadata() {
+            Ok(metadata) if metadata.is_file() => metadata,
+            _ => continue,
+        };
+        // Skip temporary files of writes in progress and quarantined corrupt files
+        let filename = entry.file_name().to_string_lossy().to_string();
+        if !filename.ends_with(".json") {
+            continue;
+        }
+        files.push(CollectionFile {
+            name: format!("{COLLECTIONS_DIR}/{filename}"),
+            path: entry.path(),
+            updated: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            size: metadata.len(),
+        });
+    }
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(files)
+}
+
+/// Removes a cached collection file
+fn remove_file(file: &CollectionFile) -> Result<()> {
+    fs::remove_file(&file.path).with_context(|| {
+        format!(
+            "Failed to remove cache file\n{}",
+            file.path.to_str().unwrap_or("...unknown path")
+        )
+    })
+}
+
+/// Prints a table of cache entries with their age, size and provider
+fn print_table(title: &str, last: &str, rows: Vec<[String; 4]>) {
+    println!("\n{}\n", title.bold());
+    if rows.is_empty() {
+        println!("  {}", "None".dim());
+        return;
+    }
+    let header = ["AGE", "SIZE", "PROVIDER", last].map(String::from);
+    let widths = (0..3)
+        .map(|i| {
+            rows.iter()
+                .chain([&header])
+                .map(|r| r[i].len())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+    let line = |r: &[String; 4]| {
+        format!(
+            "  {:<w0$}  {:<w1$}  {:<w2$}  {}",
+            r[0],
+            r[1],
+            r[2],
+            r[3],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2]
+        )
+    };
+    println!("{}", line(&header).dim());
+    for row in rows.iter() {
+        println!("{}", line(row));
+    }
+}
+
+/// Prints a success message
+fn success(message: &str) {
+    printdoc! {"\n{title} {message}\n\n",
+        title=" Success ".on_green().dim().white().bold(),
+    }
+}
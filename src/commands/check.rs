@@ -0,0 +1,87 @@
+use crate::cli::CommandCheck;
+use crate::config::lockfile::{Lockfile, LOCKFILE_FILENAME};
+use crate::config::manifest::Manifest;
+use crate::template::block::ManagedBlock;
+use crate::template::list::TemplateList;
+use crate::util::diff::unified_diff;
+use anyhow::{bail, Context, Result};
+use indoc::printdoc;
+use std::path::PathBuf;
+use std::{env, fs};
+use yansi::Paint;
+
+pub fn command(cmd: &CommandCheck) -> Result<()> {
+    let cwd = env::current_dir().with_context(|| "Failed to get current directory")?;
+
+    // Generate the expected content from the provided templates, otherwise from the manifest
+    let (expected, default_path, merge) = if cmd.templates.is_empty() {
+        let manifest_path = Manifest::resolve_path(cmd.manifest.as_deref())?;
+        let manifest = Manifest::load(&manifest_path)?;
+        let expected = if cmd.locked {
+            let lockfile = Lockfile::load(&Lockfile::path_near(&manifest_path))?;
+            manifest.content_locked(&lockfile)?
+        } else {
+            manifest.content()?
+        };
+        let output = manifest.output_path(&manifest_path);
+        (expected, output, manifest.merge)
+    } else {
+        let templates = TemplateList::parse(&cmd.templates)
+            .with_context(|| "Failed to parse provided template arguments".to_string())?;
+        let expected = if cmd.locked {
+            Lockfile::load(&cwd.join(LOCKFILE_FILENAME))?.content(&templates)?
+        } else {
+            templates.content()?
+        };
+        (expected, cwd.join(".gitignore"), false)
+    };
+
+    let path = cmd.file.as_ref().map(PathBuf::from).unwrap_or(default_path);
+    let path_str = path.to_str().unwrap_or("...unknown path");
+
+    if !path.is_file() {
+        bail!("The ignore file to check does not exist\n{path_str}")
+    }
+
+    let existing = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read ignore file at path\n{path_str}"))?;
+    let actual = generated_section(&existing, merge)
+        .with_context(|| format!("Failed to read generated content of file\n{path_str}"))?;
+
+    if is_up_to_date(&actual, &expected) {
+        printdoc! {"\n{title} {message}\n\n",
+            title=" Success ".on_green().dim().white().bold(),
+            message=format!("Ignore file is up to date: {path_str}"),
+        }
+        return Ok(());
+    }
+
+    let diff = unified_diff(
+        &normalize(&actual),
+        &normalize(&expected),
+        path_str,
+        "generated",
+    );
+    println!("\n{diff}");
+
+    bail!("Ignore file is out of date with its templates\n{path_str}")
+}
+
+/// Returns the generated part of an ignore file, which is the managed block if there is one
+pub fn generated_section(existing: &str, merge: bool) -> Result<String> {
+    match ManagedBlock::extract(existing)? {
+        Some(content) => Ok(content),
+        None if merge => bail!("No managed block found in the ignore file"),
+        None => Ok(existing.to_string()),
+    }
+}
+
+/// Checks if the generated part of an ignore file matches the expected content
+pub fn is_up_to_date(actual: &str, expected: &str) -> bool {
+    normalize(actual) == normalize(expected)
+}
+
+/// Normalizes line endings and trailing whitespace for comparison
+fn normalize(content: &str) -> String {
+    format!("{}\n", content.replace("\r\n", "\n").trim_end())
+}
@@ -1,18 +1,36 @@
 use crate::cli::{get_cli, CommandCreate};
+use crate::config::lockfile::{Lockfile, LOCKFILE_FILENAME};
+use crate::template::block::ManagedBlock;
+use crate::template::detect::{Detector, DETECT_DEPTH};
+use crate::template::simulate::Simulation;
+use crate::util::diff::unified_diff;
+use crate::util::git::{global_excludes_file, Repository};
 use anyhow::{bail, Context, Result};
 use indoc::{formatdoc, printdoc};
-use std::path::PathBuf;
+use serde::Serialize;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::{env, fs};
 use yansi::Paint;
 
 pub fn command(cmd: &CommandCreate) -> Result<()> {
     let cli = get_cli();
-    let templates = cli.templates()?;
+    let mut templates = cli.templates()?;
+
+    // Add the templates detected in the current directory after the provided ones
+    if cmd.auto {
+        let cwd = env::current_dir().with_context(|| "Failed to get current directory")?;
+        for detection in Detector::new(DETECT_DEPTH)?.detect(&cwd)? {
+            if !templates.contains(&detection.template) {
+                templates.items.push(detection.template);
+            }
+        }
+    }
 
     if templates.is_empty() {
         bail!(formatdoc! {"
             No template arguments provided
-            
+
             Provide templates to the create command using the following syntax:
              gitnr create [TEMPLATE]...
 
@@ -20,25 +38,35 @@ pub fn command(cmd: &CommandCreate) -> Result<()> {
              gitnr create --help"})
     }
 
-    let output = templates.content()?;
+    let has_out_file =
+        cmd.out_gitignore || cmd.out_file.is_some() || cmd.out_exclude || cmd.out_global;
+    let merge = is_merge(cmd);
+    if cmd.merge && !has_out_file && !cmd.simulate && !cmd.diff {
+        bail!(formatdoc! {"
+            The --merge flag requires an output file
 
-    // Write template to .gitignore file in current directory
-    if cmd.out_gitignore {
-        let cwd = env::current_dir().with_context(|| "Failed to get current directory")?;
-        let path = cwd.join(".gitignore");
-        fs::write(&path, output + "\n").with_context(|| {
-            format!(
-                "Failed to write template to .gitignore file at path\n{}",
-                path.to_str().unwrap_or("...unknown path")
-            )
-        })?;
+            Provide the file to merge the templates into using --save, --file, --exclude or --global"})
+    }
 
-        success_msg(path);
-        return Ok(());
+    if cmd.confirm && !has_out_file {
+        bail!(formatdoc! {"
+            The --confirm flag requires an output file
+
+            Provide the file to write the templates to using --save, --file, --exclude or --global"})
     }
 
-    // Write template to file path
-    if let Some(path) = &cmd.out_file {
+    let output = if cmd.locked {
+        let cwd = env::current_dir().with_context(|| "Failed to get current directory")?;
+        Lockfile::load(&cwd.join(LOCKFILE_FILENAME))?.content(&templates)?
+    } else {
+        templates.content()?
+    };
+
+    // Resolve the file to write the template to, if any
+    let out_path = if cmd.out_gitignore {
+        let cwd = env::current_dir().with_context(|| "Failed to get current directory")?;
+        Some(cwd.join(".gitignore"))
+    } else if let Some(path) = &cmd.out_file {
         let path = PathBuf::from(path);
         let path = if path.is_relative() {
             path.canonicalize().with_context(|| {
@@ -59,25 +87,221 @@ pub fn command(cmd: &CommandCreate) -> Result<()> {
                 path.to_str().unwrap_or("...unknown path")
             )
         }
+        Some(path)
+    } else if cmd.out_exclude {
+        let cwd = env::current_dir().with_context(|| "Failed to get current directory")?;
+        Some(repository(&cwd)?.exclude_file())
+    } else if cmd.out_global {
+        Some(global_excludes_file()?)
+    } else {
+        None
+    };
 
-        fs::write(&path, output + "\n").with_context(|| {
-            format!(
-                "Failed to write template to file at path\n{}",
+    // Without an output file, simulate or diff against the .gitignore file in the current directory
+    if cmd.simulate || cmd.diff {
+        let path = match out_path {
+            Some(path) => path,
+            None => env::current_dir()
+                .with_context(|| "Failed to get current directory")?
+                .join(".gitignore"),
+        };
+        if cmd.simulate {
+            let root = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            return simulate(&path, &root, &output, merge, cmd.json);
+        }
+        return print_diff(&path, &output, merge).map(|_| ());
+    }
+
+    match out_path {
+        Some(path) if cmd.dry_run => {
+            print!("{}", render_output(&path, &output, merge)?);
+            dry_run_msg(path);
+        }
+        Some(path) if cmd.confirm => {
+            if !print_diff(&path, &output, merge)? {
+                return Ok(());
+            }
+            let question = format!(
+                "Write the changes to {}?",
                 path.to_str().unwrap_or("...unknown path")
-            )
-        })?;
+            );
+            if confirm(&question)? {
+                write_output(&path, &output, merge)?;
+                success_msg(path);
+            } else {
+                println!("\n{}\n", "Nothing was written".dim());
+            }
+        }
+        Some(path) => {
+            write_output(&path, &output, merge)?;
+            success_msg(path);
+        }
+        // Print template to stdout
+        None => println!("{output}"),
+    }
+
+    Ok(())
+}
+
+/// Checks if the output is merged into the managed block of the output file. The exclude
+/// files are always merged into, as they usually hold rules written by the user.
+pub fn is_merge(cmd: &CommandCreate) -> bool {
+    cmd.merge || cmd.out_exclude || cmd.out_global
+}
+
+/// Returns the git repository containing the directory
+pub fn repository(dir: &Path) -> Result<Repository> {
+    match Repository::discover(dir) {
+        Some(repository) => Ok(repository),
+        None => bail!(
+            "Not inside a git repository, no exclude file to write to\n{}",
+            dir.to_str().unwrap_or("...unknown path")
+        ),
+    }
+}
+
+/// Prints the files affected by replacing the content of the ignore file with the output,
+/// evaluating its rules against the files in the root directory
+fn simulate(path: &Path, root: &Path, output: &str, merge: bool, json: bool) -> Result<()> {
+    let path_str = path.to_str().unwrap_or("...unknown path");
+    let current = if path.is_file() {
+        fs::read_to_string(path)
+            .with_context(|| format!("Failed to read existing file at path\n{path_str}"))?
+    } else {
+        String::new()
+    };
+    let new = render_output(path, output, merge)?;
+    let simulation = Simulation::run(root, &current, &new)?;
 
-        success_msg(path);
+    if json {
+        #[derive(Serialize)]
+        struct Output<'a> {
+            file: &'a str,
+            #[serde(flatten)]
+            simulation: &'a Simulation,
+        }
+        let output = Output {
+            file: path_str,
+            simulation: &simulation,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
         return Ok(());
     }
 
-    // Print template to stdout
-    println!("{output}");
+    let sections = [
+        ("Newly ignored", "+".red(), &simulation.ignored),
+        ("Un-ignored", "-".green(), &simulation.unignored),
+        ("Tracked but ignored", "!".yellow(), &simulation.tracked),
+    ];
+    for (title, marker, paths) in sections.iter() {
+        if paths.is_empty() {
+            continue;
+        }
+        println!("\n{}", format!("{title} ({})", paths.len()).bold());
+        for path in paths.iter() {
+            println!("  {} {path}", marker.bold());
+        }
+    }
+    if !simulation.tracked.is_empty() {
+        println!(
+            "\n{}",
+            "Ignore rules don't apply to tracked files, untrack them with `git rm --cached <path>`"
+                .dim()
+        );
+    }
+
+    let summary = if simulation.is_empty() {
+        "No files would be affected by the new rules"
+    } else {
+        "Nothing was written, the listed files would be affected by the new rules"
+    };
+    printdoc! {"\n{title} {summary}\n{files}\n\n",
+        title=" Dry Run ".on_yellow().dim().white().bold(),
+        files=format!("Evaluated {} untracked files against path: {path_str}", simulation.files).dim(),
+    }
 
     Ok(())
 }
 
-fn success_msg(path: PathBuf) {
+/// Prints a diff between the file and its content after writing the output to it, returning
+/// whether there are any changes
+pub fn print_diff(path: &Path, output: &str, merge: bool) -> Result<bool> {
+    let path_str = path.to_str().unwrap_or("...unknown path");
+    let current = if path.is_file() {
+        fs::read_to_string(path)
+            .with_context(|| format!("Failed to read existing file at path\n{path_str}"))?
+    } else {
+        String::new()
+    };
+    let new = render_output(path, output, merge)?;
+
+    let diff = unified_diff(&current, &new, path_str, "generated");
+    if diff.is_empty() {
+        printdoc! {"\n{title} {message}\n\n",
+            title=" Success ".on_green().dim().white().bold(),
+            message=format!("Ignore file is up to date: {path_str}"),
+        }
+        return Ok(false);
+    }
+    println!("\n{diff}");
+
+    Ok(true)
+}
+
+/// Asks the user a yes/no question on the terminal, defaulting to no
+fn confirm(question: &str) -> Result<bool> {
+    eprint!("{} {} ", question.bold(), "[y/N]".dim());
+    io::stderr().flush()?;
+
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .with_context(|| "Failed to read confirmation answer")?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Writes the output to the file path, either overwriting it or merging into its managed block
+pub fn write_output(path: &Path, output: &str, merge: bool) -> Result<()> {
+    let path_str = path.to_str().unwrap_or("...unknown path");
+    let content = render_output(path, output, merge)?;
+
+    // Exclude files are in directories that may not exist yet, e.g. ~/.config/git
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory for file at path\n{path_str}"))?;
+    }
+    fs::write(path, content)
+        .with_context(|| format!("Failed to write template to file at path\n{path_str}"))?;
+
+    Ok(())
+}
+
+/// Returns the content of the file path after writing the output to it
+pub fn render_output(path: &Path, output: &str, merge: bool) -> Result<String> {
+    let path_str = path.to_str().unwrap_or("...unknown path");
+
+    let content = if merge && path.is_file() {
+        let existing = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read existing file at path\n{path_str}"))?;
+        ManagedBlock::merge(&existing, output)
+            .with_context(|| format!("Failed to merge templates into file at path\n{path_str}"))?
+    } else if merge {
+        ManagedBlock::merge("", output)?
+    } else {
+        format!("{output}\n")
+    };
+
+    Ok(content)
+}
+
+fn dry_run_msg(path: PathBuf) {
+    printdoc! {"\n{title} {path}\n\n",
+        title=" Dry Run ".on_yellow().dim().white().bold(),
+        path=format!("Template would be written to path: {}", path.to_str().unwrap_or("...unknown path")),
+    }
+}
+
+pub fn success_msg(path: PathBuf) {
     printdoc! {"\n{title} {path}\n\n",
         title=" Success ".on_green().dim().white().bold(),
         path=format!("Template written to path: {}", path.to_str().unwrap_or("...unknown path")),
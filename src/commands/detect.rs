@@ -0,0 +1,47 @@
+use crate::cli::CommandDetect;
+use crate::template::detect::Detector;
+use crate::template::list::TemplateList;
+use anyhow::{Context, Result};
+use indoc::printdoc;
+use std::env;
+use std::path::PathBuf;
+use yansi::Paint;
+
+pub fn command(cmd: &CommandDetect) -> Result<()> {
+    let root = match &cmd.path {
+        Some(path) => PathBuf::from(path),
+        None => env::current_dir().with_context(|| "Failed to get current directory")?,
+    };
+
+    let detections = Detector::new(cmd.depth)?.detect(&root)?;
+
+    if detections.is_empty() {
+        printdoc! {"\n{title} {message}\n\n",
+            title=" Detect ".on_yellow().dim().white().bold(),
+            message="No templates detected for the project",
+        }
+        return Ok(());
+    }
+
+    let specs = detections
+        .iter()
+        .map(|d| d.template.spec())
+        .collect::<Result<Vec<_>>>()?;
+    let width = specs.iter().map(|s| s.len()).max().unwrap_or(0);
+
+    println!("\n{}\n", "Detected templates:".bold());
+    for (spec, detection) in specs.iter().zip(detections.iter()) {
+        println!("  {spec:<width$}   {}", detection.reason.dim());
+    }
+
+    let templates = detections.into_iter().map(|d| d.template).collect();
+    let command = TemplateList::new(templates).command()?;
+    printdoc! {"
+
+        Generate the ignore file with:
+          {command}
+
+    ", command=command.bold()};
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::cli::CommandDiff;
+use crate::commands::create::print_diff;
+use crate::config::lockfile::{Lockfile, LOCKFILE_FILENAME};
+use crate::config::manifest::Manifest;
+use crate::template::list::TemplateList;
+use anyhow::{Context, Result};
+use std::env;
+use std::path::PathBuf;
+
+pub fn command(cmd: &CommandDiff) -> Result<()> {
+    let cwd = env::current_dir().with_context(|| "Failed to get current directory")?;
+
+    // Generate the content from the provided templates, otherwise from the manifest
+    let (output, default_path, merge) = if cmd.templates.is_empty() {
+        let manifest_path = Manifest::resolve_path(cmd.manifest.as_deref())?;
+        let manifest = Manifest::load(&manifest_path)?;
+        let output = if cmd.locked {
+            let lockfile = Lockfile::load(&Lockfile::path_near(&manifest_path))?;
+            manifest.content_locked(&lockfile)?
+        } else {
+            manifest.content()?
+        };
+        let out_path = manifest.output_path(&manifest_path);
+        (output, out_path, manifest.merge)
+    } else {
+        let templates = TemplateList::parse(&cmd.templates)
+            .with_context(|| "Failed to parse provided template arguments".to_string())?;
+        let output = if cmd.locked {
+            Lockfile::load(&cwd.join(LOCKFILE_FILENAME))?.content(&templates)?
+        } else {
+            templates.content()?
+        };
+        (output, cwd.join(".gitignore"), false)
+    };
+
+    let path = cmd.file.as_ref().map(PathBuf::from).unwrap_or(default_path);
+    print_diff(&path, &output, merge || cmd.merge)?;
+
+    Ok(())
+}
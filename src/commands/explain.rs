@@ -0,0 +1,140 @@
+use crate::cli::CommandExplain;
+use crate::template::list::TemplateList;
+use crate::template::pattern::{Explanation, Rule, Rules};
+use anyhow::{bail, Context, Result};
+use std::path::{Component, Path, PathBuf};
+use std::{env, fs};
+use yansi::Paint;
+
+pub fn command(cmd: &CommandExplain) -> Result<()> {
+    let cwd = env::current_dir().with_context(|| "Failed to get current directory")?;
+
+    // Evaluate against the content generated from the templates, otherwise the ignore file
+    let (content, base) = if cmd.templates.is_empty() {
+        let path = match &cmd.file {
+            Some(file) => normalize(&cwd.join(file)),
+            None => cwd.join(".gitignore"),
+        };
+        let path_str = path.to_str().unwrap_or("...unknown path");
+        if !path.is_file() {
+            bail!("The ignore file to explain the paths with does not exist\n{path_str}")
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read ignore file at path\n{path_str}"))?;
+        let base = path.parent().map(Path::to_path_buf).unwrap_or(cwd.clone());
+        (content, base)
+    } else {
+        let templates = TemplateList::parse(&cmd.templates)
+            .with_context(|| "Failed to parse provided template arguments".to_string())?;
+        (templates.content()?, cwd.clone())
+    };
+
+    let rules = Rules::parse(&content);
+    for input in cmd.paths.iter() {
+        let path = normalize(&cwd.join(input));
+        let relative = match path.strip_prefix(&base) {
+            Ok(relative) if relative.as_os_str().is_empty() => {
+                bail!("Cannot explain the ignore file directory itself: {input}")
+            }
+            Ok(relative) => relative,
+            Err(_) => bail!(
+                "Path is outside of the ignore file directory: {input}\n{}",
+                base.to_str().unwrap_or("...unknown path")
+            ),
+        };
+        let relative = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let is_dir = path.is_dir() || input.ends_with(['/', '\\']);
+        print_explanation(input, &rules.explain(&relative, is_dir));
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Prints the rules matching a path and whether it's ignored
+fn print_explanation(input: &str, explanation: &Explanation) {
+    let status = if explanation.ignored {
+        "ignored".red().bold()
+    } else {
+        "not ignored".green().bold()
+    };
+    println!("\n{}  {status}", input.bold());
+
+    if let Some((parent, rule)) = &explanation.ignored_parent {
+        println!(
+            "  {}",
+            format!("Parent directory '{parent}/' is ignored, so a file in it can't be un-ignored")
+                .dim()
+        );
+        print_rules(&[rule], None);
+    }
+    if explanation.matches.is_empty() {
+        let message = match explanation.ignored_parent {
+            Some(_) => "No rules match the path itself",
+            None => "No rules match the path",
+        };
+        println!("  {}", message.dim());
+        return;
+    }
+
+    // The last matching rule decides, unless a parent directory is ignored
+    let deciding = match explanation.ignored_parent {
+        Some(_) => None,
+        None => explanation.matches.last().copied(),
+    };
+    print_rules(&explanation.matches, deciding);
+}
+
+/// Prints rules with their line number, effect and template section
+fn print_rules(rules: &[&Rule], deciding: Option<&Rule>) {
+    let number_width = rules
+        .iter()
+        .map(|r| r.line_number.to_string().len())
+        .max()
+        .unwrap_or(0);
+    let line_width = rules
+        .iter()
+        .map(|r| r.pattern.line.trim().len())
+        .max()
+        .unwrap_or(0);
+
+    for rule in rules.iter() {
+        let effect = if rule.pattern.negated {
+            "un-ignores"
+        } else {
+            "ignores"
+        };
+        let line = format!(
+            "  line {:>number_width$}  {:<line_width$}  {:<10}  {}",
+            rule.line_number,
+            rule.pattern.line.trim(),
+            effect,
+            rule.section.as_deref().unwrap_or("")
+        );
+        if deciding.is_some_and(|d| std::ptr::eq(d, *rule)) {
+            println!("{}", line.bold());
+        } else {
+            println!("{line}");
+        }
+    }
+}
+
+/// Lexically normalizes a path, resolving any `.` and `..` components without requiring the
+/// path to exist
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
@@ -1,3 +1,10 @@
+pub mod cache;
+pub mod check;
 pub mod completions;
 pub mod create;
+pub mod detect;
+pub mod diff;
+pub mod explain;
 pub mod search;
+pub mod sync;
+pub mod update;
@@ -1,3 +1,4 @@
+use crate::commands::search::state::loading::UILoaded;
 use anyhow::Result;
 use ratatui::crossterm::event::{self, Event as CrosstermEvent, KeyEvent, MouseEvent};
 use std::sync::mpsc;
@@ -5,7 +6,7 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 /// Terminal events.
-#[derive(Clone, Copy, Debug)]
+#[derive(Debug)]
 pub enum Event {
     /// Terminal tick.
     Tick,
@@ -15,6 +16,8 @@ pub enum Event {
     Mouse(MouseEvent),
     /// Terminal resize.
     Resize(u16, u16),
+    /// Content loaded in a background thread.
+    Loaded(UILoaded),
 }
 
 /// Terminal event handler.
@@ -67,6 +70,11 @@ impl EventHandler {
         }
     }
 
+    /// Returns a sender for background threads to send events to the handler.
+    pub fn sender(&self) -> mpsc::Sender<Event> {
+        self.sender.clone()
+    }
+
     /// Receive the next event from the handler thread.
     ///
     /// This function will always block the current thread if
@@ -83,15 +83,16 @@ impl<B: Backend> Tui<B> {
     /// the terminal properties if unexpected errors occur.
     fn reset() -> Result<()> {
         terminal::disable_raw_mode()?;
+        // Reset on stderr where the interface is rendered, stdout may be redirected to a file
         if cfg!(target_family = "unix") {
             ratatui::crossterm::execute!(
-                io::stdout(),
+                io::stderr(),
                 LeaveAlternateScreen,
                 DisableMouseCapture,
                 PopKeyboardEnhancementFlags
             )?;
         } else {
-            ratatui::crossterm::execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+            ratatui::crossterm::execute!(io::stderr(), LeaveAlternateScreen, DisableMouseCapture)?;
         }
         Ok(())
     }
@@ -1,6 +1,7 @@
 use crate::commands::search::framework::tui::Tui;
-use crate::commands::search::state::view_preview::{UIStatePreview, UIStatePreviewState};
-use crate::commands::search::state::{UIState, UIStateView};
+use crate::commands::search::state::loading::UILoaded;
+use crate::commands::search::state::view_preview::UIStatePreviewState;
+use crate::commands::search::state::{UIFocus, UIState, UIStateView};
 use crate::template::list::TemplateList;
 use anyhow::Result;
 use ratatui::backend::CrosstermBackend;
@@ -12,11 +13,7 @@ use std::time::{Duration, Instant};
 use tui_input::backend::crossterm::EventHandler;
 
 /// Handles the keyboard events for the UI
-pub fn handle_key_events(
-    event: KeyEvent,
-    app: &mut UIState,
-    tui: &mut Tui<CrosstermBackend<Stderr>>,
-) -> Result<()> {
+pub fn handle_key_events(event: KeyEvent, app: &mut UIState) -> Result<()> {
     // Ignore release events to prevent executing the same action twice
     // [Issue] https://github.com/reemus-dev/gitnr/issues/3
     if event.kind == KeyEventKind::Release {
@@ -36,6 +33,28 @@ pub fn handle_key_events(
         return Ok(());
     }
 
+    // ---------------
+    // Error popup: retry with R, dismiss with any other key
+    // ---------------
+    if !app.errors.is_empty() {
+        let is_retry = matches!(event.code, KeyCode::Char('r') | KeyCode::Char('R'));
+        app.error_dismiss(is_retry);
+        return Ok(());
+    }
+
+    // ---------------
+    // Loading preview: cancel with Esc
+    // ---------------
+    if app.preview_loading.is_some() {
+        if event.code == KeyCode::Esc {
+            app.preview_loading = None;
+        }
+        return Ok(());
+    }
+
+    // Upper case letters typed in the filter input aren't shortcuts
+    let is_filtering = app.focus == UIFocus::List && !app.collection_filter.value().is_empty();
+
     // ---------------
     // Handle shift modified keys presses
     // ---------------
@@ -44,18 +63,29 @@ pub fn handle_key_events(
             UIStateView::Home => match event.code {
                 // Shift + S: previews the currently selected templates
                 KeyCode::Char('s') | KeyCode::Char('S') => {
-                    // Clear the terminal before changing the view to prevent weird left overs
-                    tui.clear()?;
-                    let templates = app
-                        .selected
-                        .lock()
-                        .unwrap()
-                        .iter()
-                        .map(|s| s.template.clone())
-                        .collect::<Vec<_>>();
-                    let templates = TemplateList::new(templates);
-                    let preview_state = UIStatePreview::new(templates)?;
-                    app.view = UIStateView::Preview(preview_state);
+                    app.preview(app.selected_templates());
+                    return Ok(());
+                }
+                // Shift + X: removes all the selected templates from the selection list
+                KeyCode::Char('x') | KeyCode::Char('X') if app.focus == UIFocus::Selection => {
+                    app.selected_clear();
+                    return Ok(());
+                }
+                // Shift + R: loads the collections that failed to load in the current tab again
+                KeyCode::Char('r') | KeyCode::Char('R') if !is_filtering => {
+                    app.collection_retry();
+                    return Ok(());
+                }
+                // Shift + P: shows or hides the live preview pane of the highlighted template
+                KeyCode::Char('p') | KeyCode::Char('P') if !is_filtering => {
+                    app.live_preview.toggle();
+                    return Ok(());
+                }
+                // Shift + C: previews the currently highlighted template in the selection list
+                KeyCode::Char('c') | KeyCode::Char('C') if app.focus == UIFocus::Selection => {
+                    if let Some(template) = app.selected_highlighted() {
+                        app.preview(TemplateList::new(vec![template]));
+                    }
                     return Ok(());
                 }
                 // Shift + C: previews the currently highlighted template in the list
@@ -64,13 +94,9 @@ pub fn handle_key_events(
                     match index {
                         None => {}
                         Some(index) => {
-                            let template = app.collection().values.get(index).cloned();
-                            if let Some(template) = template {
-                                // Clear the terminal before changing the view to prevent weird left overs
-                                tui.clear()?;
-                                let templates = TemplateList::new(vec![template]);
-                                let preview_state = UIStatePreview::new(templates)?;
-                                app.view = UIStateView::Preview(preview_state);
+                            let item = app.collection().values.get(index).cloned();
+                            if let Some(item) = item {
+                                app.preview(TemplateList::new(vec![item.template]));
                             }
                             return Ok(());
                         }
@@ -78,19 +104,81 @@ pub fn handle_key_events(
                 }
                 _ => {}
             },
-            UIStateView::Preview(ref mut p) => match event.code {
+            // Dialogs handle their own input, e.g. upper case letters in the file path prompt
+            UIStateView::Preview(ref mut p) if !p.is_dialog() => match event.code {
                 KeyCode::Char('c') | KeyCode::Char('C') => p.copy_content()?,
                 KeyCode::Char('x') | KeyCode::Char('X') => p.copy_command()?,
+                KeyCode::Char('m') | KeyCode::Char('M') => p.save_manifest()?,
+                KeyCode::Char('d') | KeyCode::Char('D') => p.toggle_diff()?,
+                // Save actions return early so the same key press doesn't close their popup
+                KeyCode::Char('w') | KeyCode::Char('W') => return p.save_gitignore(),
+                KeyCode::Char('f') | KeyCode::Char('F') => {
+                    p.prompt_path();
+                    return Ok(());
+                }
+                KeyCode::Char('b') | KeyCode::Char('B') => return p.save_block(),
+                KeyCode::Char('e') | KeyCode::Char('E') => return p.save_exclude(),
+                // Exit and print the template or the CLI command to stdout
+                KeyCode::Char('o') | KeyCode::Char('O') => {
+                    app.output = Some(p.content.to_owned());
+                    app.quit();
+                    return Ok(());
+                }
+                KeyCode::Char('l') | KeyCode::Char('L') => {
+                    app.output = Some(p.command.to_owned());
+                    app.quit();
+                    return Ok(());
+                }
                 _ => {}
             },
+            UIStateView::Preview(_) => {}
         }
     }
 
+    // ---------------
+    // View: Home, with the selection list focused
+    // ---------------
+    if let (UIStateView::Home, UIFocus::Selection) = (&app.view, app.focus) {
+        match event.code {
+            // ---------------
+            // Return to the template list: Tab & Esc
+            // ---------------
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Esc => app.focus_toggle(),
+            // ---------------
+            // Change template
+            //  - Up & Down Arrow          (next / previous)
+            //  - Shift + Up & Down Arrow  (move template up / down)
+            // ---------------
+            KeyCode::Up if is_shift => app.selected_move(true),
+            KeyCode::Down if is_shift => app.selected_move(false),
+            KeyCode::Up => app.selected_previous(),
+            KeyCode::Down => app.selected_next(),
+            // ---------------
+            // Remove template: Delete & Backspace
+            // ---------------
+            KeyCode::Delete | KeyCode::Backspace => app.selected_remove(),
+            // ---------------
+            // Preview template: Enter
+            // ---------------
+            KeyCode::Enter => {
+                if let Some(template) = app.selected_highlighted() {
+                    app.preview(TemplateList::new(vec![template]));
+                }
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+
     // ---------------
     // View: Home
     // ---------------
     if let UIStateView::Home = &mut app.view {
         match event.code {
+            // ---------------
+            // Focus the selection list: Tab
+            // ---------------
+            KeyCode::Tab | KeyCode::BackTab => app.focus_toggle(),
             // ---------------
             // Change tabs: left & right arrow
             // ---------------
@@ -150,7 +238,7 @@ pub fn handle_key_events(
     // View: Preview
     // ---------------
     if let UIStateView::Preview(ref mut p) = &mut app.view {
-        match &p.state {
+        match &mut p.state {
             UIStatePreviewState::Default => match event.code {
                 // Return to home view with ESC
                 KeyCode::Esc => {
@@ -173,6 +261,22 @@ pub fn handle_key_events(
                 KeyCode::Char('x') | KeyCode::Char('X') => {}
                 _ => p.copy_done(),
             },
+            UIStatePreviewState::SavedManifest => match event.code {
+                KeyCode::Char('m') | KeyCode::Char('M') => {}
+                _ => p.copy_done(),
+            },
+            UIStatePreviewState::PromptPath(ref mut input) => match event.code {
+                KeyCode::Esc => p.copy_done(),
+                KeyCode::Enter => p.submit_path()?,
+                _ => {
+                    input.handle_event(&Event::Key(event));
+                }
+            },
+            UIStatePreviewState::ConfirmOverwrite(_) => match event.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => p.confirm_overwrite(),
+                _ => p.copy_done(),
+            },
+            UIStatePreviewState::Saved(_) | UIStatePreviewState::SaveFailed(_) => p.copy_done(),
         }
     }
 
@@ -187,6 +291,16 @@ pub fn handle_mouse_events(event: MouseEvent, app: &mut UIState) -> Result<()> {
     let is_alt = event.modifiers == KeyModifiers::ALT;
     let is_shift = event.modifiers == KeyModifiers::SHIFT;
 
+    // ---------------
+    // Closing the error popup with mouse click
+    // ---------------
+    if !app.errors.is_empty() || app.preview_loading.is_some() {
+        if let MouseEventKind::Down(MouseButton::Left) = event.kind {
+            app.error_dismiss(false);
+        }
+        return Ok(());
+    }
+
     // ---------------
     // View: Home
     // ---------------
@@ -195,27 +309,26 @@ pub fn handle_mouse_events(event: MouseEvent, app: &mut UIState) -> Result<()> {
             // Select a template with left-click
             MouseEventKind::Down(MouseButton::Left) => app.list_select(),
             // Scrolling on the collection templates list
-            MouseEventKind::ScrollUp => {
-                if now.duration_since(app.last_scroll_time) > Duration::from_millis(15) {
-                    app.list_previous(if is_shift || is_alt {
-                        Some(10)
-                    } else {
-                        Some(1)
-                    });
-                    app.last_scroll_time = now;
-                }
+            MouseEventKind::ScrollUp
+                if now.duration_since(app.last_scroll_time) > Duration::from_millis(15) =>
+            {
+                app.list_previous(if is_shift || is_alt {
+                    Some(10)
+                } else {
+                    Some(1)
+                });
+                app.last_scroll_time = now;
             }
             // Scrolling on the collection templates list
-            MouseEventKind::ScrollDown => {
-                if now.duration_since(app.last_scroll_time) > Duration::from_millis(15) {
-                    app.list_next(if is_shift || is_alt {
-                        println!("Scrolling {is_shift} {is_alt}");
-                        Some(10)
-                    } else {
-                        Some(1)
-                    });
-                    app.last_scroll_time = now;
-                }
+            MouseEventKind::ScrollDown
+                if now.duration_since(app.last_scroll_time) > Duration::from_millis(15) =>
+            {
+                app.list_next(if is_shift || is_alt {
+                    Some(10)
+                } else {
+                    Some(1)
+                });
+                app.last_scroll_time = now;
             }
             _ => {}
         }
@@ -243,3 +356,22 @@ pub fn handle_mouse_events(event: MouseEvent, app: &mut UIState) -> Result<()> {
 
     Ok(())
 }
+
+/// Handles the content loaded in background threads for the UI
+pub fn handle_loaded_events(
+    loaded: UILoaded,
+    app: &mut UIState,
+    tui: &mut Tui<CrosstermBackend<Stderr>>,
+) -> Result<()> {
+    match loaded {
+        UILoaded::Collection(kind, items) => app.collection_loaded(kind, items),
+        UILoaded::Preview(templates, preview) => {
+            // Clear the terminal when changing the view to prevent weird left overs
+            if app.preview_loaded(templates, preview) {
+                tui.clear()?;
+            }
+        }
+        UILoaded::Template(template, content) => app.live_preview.loaded(template, content),
+    }
+    Ok(())
+}
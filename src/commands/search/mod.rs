@@ -1,25 +1,32 @@
 mod framework;
 mod handlers;
-mod state;
+pub(crate) mod state;
 mod views;
 
 use crate::commands::search::framework::event::{Event, EventHandler};
 use crate::commands::search::framework::tui::Tui;
-use crate::commands::search::handlers::{handle_key_events, handle_mouse_events};
+use crate::commands::search::handlers::{
+    handle_key_events, handle_loaded_events, handle_mouse_events,
+};
 use crate::commands::search::state::UIState;
+use crate::util::fs::set_auto_offline;
 use anyhow::Result;
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 use std::io;
 
 pub fn command() -> Result<()> {
-    // Create an application state
-    let mut app = UIState::new()?;
+    // Collections that fail to load because the network is unreachable can be retried, so
+    // the rest of the run isn't switched to offline mode
+    set_auto_offline(false);
+
+    // Create an application state, which loads the template collections in the background
+    let events = EventHandler::new(100);
+    let mut app = UIState::new(events.sender());
 
     // Initialize the terminal user interface.
     let backend = CrosstermBackend::new(io::stderr());
     let terminal = Terminal::new(backend)?;
-    let events = EventHandler::new(250);
     let mut tui = Tui::new(terminal, events);
     tui.init()?;
 
@@ -28,16 +35,28 @@ pub fn command() -> Result<()> {
         // Render the user interface.
         tui.draw(&mut app)?;
 
-        // Handle events.
-        match tui.events.next()? {
-            Event::Tick => app.tick(),
-            Event::Key(key_event) => handle_key_events(key_event, &mut app, &mut tui)?,
-            Event::Mouse(mouse_event) => handle_mouse_events(mouse_event, &mut app)?,
-            Event::Resize(_, _) => {}
+        // Handle events, showing any errors in a popup instead of exiting
+        let result = match tui.events.next()? {
+            Event::Tick => {
+                app.tick();
+                Ok(())
+            }
+            Event::Key(key_event) => handle_key_events(key_event, &mut app),
+            Event::Mouse(mouse_event) => handle_mouse_events(mouse_event, &mut app),
+            Event::Resize(_, _) => Ok(()),
+            Event::Loaded(loaded) => handle_loaded_events(loaded, &mut app, &mut tui),
+        };
+        if let Err(e) = result {
+            app.error(e, None);
         }
     }
 
     // Exit the user interface.
     tui.exit()?;
+
+    // The terminal UI is rendered to stderr, so the output can be redirected to a file
+    if let Some(output) = app.output {
+        println!("{output}");
+    }
     Ok(())
 }
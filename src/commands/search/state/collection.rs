@@ -1,39 +1,149 @@
 use crate::template::collection::{TemplateCollection, TemplateCollectionKind};
 use crate::template::item::Template;
 use anyhow::Result;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use once_cell::sync::Lazy;
 use ratatui::widgets::ListState;
+use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 
+/// Providers whose collections list the same templates, only merged once in the "All" list
+const MIRRORED_PROVIDERS: [&str; 2] = ["gh:", "tt:"];
+
+static MATCHER: Lazy<SkimMatcherV2> = Lazy::new(|| SkimMatcherV2::default().ignore_case());
+
+/// Whether the templates of a collection list are available
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UICollectionStatus {
+    /// The templates are being loaded in a background thread
+    Loading,
+    Loaded,
+    /// The templates failed to load, with the error message
+    Failed(String),
+}
+
 /// Stores the data and state for a template collection list
 #[derive(Debug)]
 pub struct UICollection {
-    /// The underlying template collection
-    pub collection: TemplateCollection,
+    /// The template collection shown in the list, or the "All" pseudo collection
+    pub kind: TemplateCollectionKind,
+    /// Whether the list merges the templates of all collections
+    pub is_all: bool,
+    /// Whether the templates of the list are available
+    pub status: UICollectionStatus,
+    /// All the templates of the list
+    pub items: Vec<UICollectionItem>,
     /// The state of the list
     pub state: Arc<Mutex<ListState>>,
     /// The current values of the list to display depending on any filters
-    pub values: Vec<Template>,
+    pub values: Vec<UICollectionItem>,
+}
+
+/// A template in a collection list
+#[derive(Debug, Clone)]
+pub struct UICollectionItem {
+    /// The collection the template is listed in
+    pub kind: TemplateCollectionKind,
+    pub template: Template,
+    /// The display name of the template
+    pub name: String,
+    /// Indices of the name characters matching the filter, to highlight them
+    pub matches: Vec<usize>,
 }
 
 impl UICollection {
-    /// Constructs a new instance of [`UICollection`].
-    pub fn new(kind: TemplateCollectionKind) -> Result<UICollection> {
-        let collection = TemplateCollection::new(kind)?;
+    /// Constructs a new instance of [`UICollection`], without templates until they're loaded
+    pub fn new(kind: TemplateCollectionKind) -> UICollection {
+        Self::empty(kind, false)
+    }
+
+    /// Constructs the list of the templates of all the collections, see [`UICollection::merge`]
+    pub fn all() -> UICollection {
+        let kind = TemplateCollectionKind {
+            provider: String::new(),
+            id: "all".to_string(),
+            name: "All".to_string(),
+        };
+        Self::empty(kind, true)
+    }
+
+    /// Loads the templates of the collection
+    pub fn load(kind: &TemplateCollectionKind) -> Result<Vec<UICollectionItem>> {
+        let collection = TemplateCollection::new(kind.clone())?;
+        collection
+            .items
+            .iter()
+            .map(|template| UICollectionItem::new(&collection.kind, template))
+            .collect()
+    }
 
-        // Default values to all the templates in the collection
-        let values = collection.items.clone();
+    /// Merges the templates of all the collections, in collection order. The TopTal collection
+    /// mirrors most of the GitHub templates, so templates with the same name in both are only
+    /// listed once, from the first of them. Templates of any other collection are all listed.
+    pub fn merge(collections: &[Arc<Mutex<UICollection>>]) -> Vec<UICollectionItem> {
+        let mut names: HashSet<String> = HashSet::new();
+        let mut items: Vec<UICollectionItem> = Vec::new();
+        for collection in collections.iter() {
+            for item in collection.lock().unwrap().items.iter() {
+                let mirrored = MIRRORED_PROVIDERS.contains(&item.kind.provider.as_str());
+                if !mirrored || names.insert(item.name.to_lowercase()) {
+                    items.push(item.clone());
+                }
+            }
+        }
+        items
+    }
+
+    /// Replaces the templates of the list, applying the filter and keeping the selection
+    pub fn set_items(&mut self, items: Vec<UICollectionItem>, filter: &str) {
+        self.items = items;
+        self.filter(filter);
+    }
 
+    fn empty(kind: TemplateCollectionKind, is_all: bool) -> Self {
         // Create the list state and set the first item as selected
         let mut state = ListState::default();
         state.select(Some(0));
 
         let state = Arc::new(Mutex::new(state));
 
-        Ok(UICollection {
-            collection,
-            values,
+        Self {
+            kind,
+            is_all,
+            status: UICollectionStatus::Loading,
+            items: Vec::new(),
+            values: Vec::new(),
             state,
-        })
+        }
+    }
+
+    /// Filters the list values by fuzzy matching the template names, ranked by their score
+    pub fn filter(&mut self, filter: &str) {
+        if filter.is_empty() {
+            self.values = self.items.clone();
+        } else {
+            let mut values = self
+                .items
+                .iter()
+                .filter_map(|item| {
+                    let (score, matches) = MATCHER.fuzzy_indices(&item.name, filter)?;
+                    let item = UICollectionItem {
+                        matches,
+                        ..item.clone()
+                    };
+                    Some((score, item))
+                })
+                .collect::<Vec<_>>();
+            // The sort is stable so templates with the same score keep the collection order
+            values.sort_by_key(|(score, _)| -score);
+            self.values = values.into_iter().map(|(_, item)| item).collect();
+        }
+
+        // Keep the selection within the list
+        let mut state = self.state.lock().unwrap();
+        let selected = state.selected().unwrap_or(0);
+        state.select(Some(selected.min(self.values.len().saturating_sub(1))));
     }
 
     /// Selects the next item in the list
@@ -61,6 +171,17 @@ impl UICollection {
     }
 }
 
+impl UICollectionItem {
+    pub fn new(kind: &TemplateCollectionKind, template: &Template) -> Result<Self> {
+        Ok(Self {
+            kind: kind.clone(),
+            template: template.clone(),
+            name: template.value.name()?,
+            matches: Vec::new(),
+        })
+    }
+}
+
 /// Represents an select item from the template collection list
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UICollectionSelection {
@@ -0,0 +1,87 @@
+use crate::commands::search::framework::event::Event;
+use crate::commands::search::state::loading::load_template;
+use crate::template::item::Template;
+use anyhow::Result;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// How long a template has to stay highlighted before its content is loaded, so scrolling
+/// through the list doesn't fetch every template passed along the way
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// The content shown in the live preview pane
+#[derive(Debug, Clone, Default)]
+pub enum UILivePreviewContent {
+    /// No template is highlighted
+    #[default]
+    Empty,
+    /// The content of the template is being loaded
+    Loading,
+    Loaded(String),
+    /// The content failed to load, with the error message
+    Failed(String),
+}
+
+/// State of the live preview pane on the home view, which shows the content of the
+/// highlighted template. The content is loaded in a background thread on tick events.
+#[derive(Debug, Default)]
+pub struct UILivePreview {
+    /// Whether the pane is shown
+    pub enabled: bool,
+    /// The template the content is shown or loaded for
+    pub template: Option<Template>,
+    pub content: UILivePreviewContent,
+    /// The currently highlighted template and since when
+    highlighted: Option<(Template, Instant)>,
+}
+
+impl UILivePreview {
+    /// Shows or hides the pane
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    /// Updates the pane on a tick event given the currently highlighted template, loading its
+    /// content once it has been highlighted for long enough
+    pub fn tick(&mut self, highlighted: Option<Template>, events: &mpsc::Sender<Event>) {
+        if !self.enabled {
+            return;
+        }
+
+        // Restart the debounce timer whenever the highlighted template changes
+        let changed = match (&self.highlighted, &highlighted) {
+            (Some((current, _)), Some(template)) => current != template,
+            (None, None) => false,
+            _ => true,
+        };
+        if changed {
+            self.highlighted = highlighted.map(|t| (t, Instant::now()));
+        }
+
+        match &self.highlighted {
+            None => {
+                self.template = None;
+                self.content = UILivePreviewContent::Empty;
+            }
+            Some((template, since)) => {
+                if self.template.as_ref() != Some(template) && since.elapsed() >= DEBOUNCE {
+                    load_template(template.clone(), events);
+                    self.template = Some(template.clone());
+                    self.content = UILivePreviewContent::Loading;
+                }
+            }
+        }
+    }
+
+    /// Shows the loaded content of the template, unless another template was highlighted in
+    /// the meantime
+    pub fn loaded(&mut self, template: Template, content: Result<String>) {
+        if self.template.as_ref() != Some(&template) {
+            return;
+        }
+        self.content = match content {
+            Ok(content) => UILivePreviewContent::Loaded(content),
+            Err(e) => UILivePreviewContent::Failed(format!("{e:#}")),
+        };
+    }
+}
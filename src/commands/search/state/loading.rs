@@ -0,0 +1,66 @@
+use crate::commands::search::framework::event::Event;
+use crate::commands::search::state::collection::{UICollection, UICollectionItem};
+use crate::commands::search::state::view_preview::UIStatePreview;
+use crate::template::collection::TemplateCollectionKind;
+use crate::template::item::Template;
+use crate::template::list::TemplateList;
+use anyhow::Result;
+use std::sync::mpsc;
+use std::thread;
+
+/// Content loaded in a background thread, sent to the terminal UI through the event channel
+#[derive(Debug)]
+pub enum UILoaded {
+    /// The templates of a collection
+    Collection(TemplateCollectionKind, Result<Vec<UICollectionItem>>),
+    /// The preview of the templates
+    Preview(TemplateList, Result<UIStatePreview>),
+    /// The content of the template highlighted in the live preview pane
+    Template(Template, Result<String>),
+}
+
+/// An action that failed to load, which can be retried from its error popup
+#[derive(Debug, Clone)]
+pub enum UIRetry {
+    Collection(TemplateCollectionKind),
+    Preview(TemplateList),
+}
+
+/// An error shown in a popup until dismissed
+#[derive(Debug, Clone)]
+pub struct UIError {
+    pub message: String,
+    pub retry: Option<UIRetry>,
+}
+
+/// Loads the templates of the collection in a background thread
+pub fn load_collection(kind: TemplateCollectionKind, events: &mpsc::Sender<Event>) {
+    spawn(events, move || {
+        let items = UICollection::load(&kind);
+        UILoaded::Collection(kind, items)
+    });
+}
+
+/// Loads the preview of the templates in a background thread
+pub fn load_preview(templates: TemplateList, events: &mpsc::Sender<Event>) {
+    spawn(events, move || {
+        let preview = UIStatePreview::new(templates.clone());
+        UILoaded::Preview(templates, preview)
+    });
+}
+
+/// Loads the content of the template in a background thread
+pub fn load_template(template: Template, events: &mpsc::Sender<Event>) {
+    spawn(events, move || {
+        let content = template.content_body();
+        UILoaded::Template(template, content)
+    });
+}
+
+fn spawn(events: &mpsc::Sender<Event>, load: impl FnOnce() -> UILoaded + Send + 'static) {
+    let events = events.clone();
+    thread::spawn(move || {
+        // The receiver is gone if the terminal UI exited in the meantime
+        let _ = events.send(Event::Loaded(load()));
+    });
+}
@@ -1,14 +1,30 @@
 /// Stateful list widget
 pub mod collection;
 
+/// Live preview pane state
+pub mod live_preview;
+
+/// Background loading of templates
+pub mod loading;
+
 /// Application preview state
 pub mod view_preview;
 
-use crate::commands::search::state::collection::{UICollection, UICollectionSelection};
+use crate::cli::get_cli;
+use crate::commands::search::framework::event::Event;
+use crate::commands::search::state::collection::{
+    UICollection, UICollectionItem, UICollectionSelection, UICollectionStatus,
+};
+use crate::commands::search::state::live_preview::UILivePreview;
+use crate::commands::search::state::loading::{load_collection, load_preview, UIError, UIRetry};
 use crate::commands::search::state::view_preview::UIStatePreview;
 use crate::template::collection::TemplateCollectionKind;
+use crate::template::item::Template;
+use crate::template::list::TemplateList;
+use crate::util::notes::note;
 use anyhow::Result;
-use std::sync::{Arc, Mutex, MutexGuard};
+use ratatui::widgets::ListState;
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
 use std::time::Instant;
 use tui_input::Input;
 
@@ -21,6 +37,15 @@ pub enum UIStateView {
     Preview(UIStatePreview),
 }
 
+/// The pane of the home view receiving the keyboard input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UIFocus {
+    /// The template collection list and its filter input
+    List,
+    /// The selected templates list
+    Selection,
+}
+
 /// Application state
 #[derive(Debug)]
 pub struct UIState {
@@ -28,8 +53,14 @@ pub struct UIState {
     pub running: bool,
     /// The current view of the terminal UI
     pub view: UIStateView,
-    /// Selected templates
+    /// Selected templates, in the order they're generated in
     pub selected: Arc<Mutex<Vec<UICollectionSelection>>>,
+    /// The state of the selected templates list
+    pub selected_state: ListState,
+    /// The CLI command generating the selected templates
+    pub selected_command: String,
+    /// The pane of the home view receiving the keyboard input
+    pub focus: UIFocus,
     /// Index of the template collection tabs
     pub collection_tab: usize,
     /// Filter input for filtering the current template collection tab
@@ -38,46 +69,205 @@ pub struct UIState {
     pub collections: Vec<Arc<Mutex<UICollection>>>,
     /// The last time the user has scrolled the terminal (for debouncing scroll events)
     pub last_scroll_time: Instant,
+    /// The live preview pane of the highlighted template
+    pub live_preview: UILivePreview,
+    /// Content to print to stdout after exiting the terminal UI
+    pub output: Option<String>,
+    /// Errors shown in popups until dismissed, the first one is shown
+    pub errors: Vec<UIError>,
+    /// The templates whose preview is being loaded
+    pub preview_loading: Option<TemplateList>,
+    /// Number of tick events so far, used to animate the loading indicators
+    pub ticks: usize,
+    /// Sends the content loaded in background threads to the event handler
+    events: mpsc::Sender<Event>,
 }
 
 impl UIState {
-    /// Constructs a new instance of [`UIState`].
-    pub fn new() -> Result<Self> {
-        let collection = |kind: TemplateCollectionKind| -> Result<Arc<Mutex<UICollection>>> {
-            let collection = UICollection::new(kind)?;
-            Ok(Arc::new(Mutex::new(collection)))
-        };
+    /// Constructs a new instance of [`UIState`], loading the template collections in the
+    /// background and sending them through the events channel
+    pub fn new(events: mpsc::Sender<Event>) -> Self {
+        // The first tab lists the templates of all collections
+        let mut collections = vec![Arc::new(Mutex::new(UICollection::all()))];
+        for kind in TemplateCollectionKind::all() {
+            load_collection(kind.clone(), &events);
+            collections.push(Arc::new(Mutex::new(UICollection::new(kind))));
+        }
 
-        Ok(Self {
+        Self {
             running: true,
             view: UIStateView::Home,
             selected: Arc::new(Mutex::new(vec![])),
+            selected_state: ListState::default(),
+            selected_command: String::new(),
+            focus: UIFocus::List,
             collection_tab: 0,
             collection_filter: Input::default(),
-            collections: vec![
-                collection(TemplateCollectionKind::TopTal)?,
-                collection(TemplateCollectionKind::GitHub)?,
-                collection(TemplateCollectionKind::GitHubGlobal)?,
-                collection(TemplateCollectionKind::GitHubCommunity)?,
-            ],
+            collections,
             last_scroll_time: Instant::now(),
-        })
+            live_preview: UILivePreview::default(),
+            output: None,
+            errors: Vec::new(),
+            preview_loading: None,
+            ticks: 0,
+            events,
+        }
     }
 
     /// Handles the tick event of the terminal.
-    pub fn tick(&self) {}
+    pub fn tick(&mut self) {
+        self.ticks = self.ticks.wrapping_add(1);
+        let highlighted = match (&self.view, self.focus) {
+            (UIStateView::Home, UIFocus::List) => self.list_highlighted(),
+            (UIStateView::Home, UIFocus::Selection) => self.selected_highlighted(),
+            (UIStateView::Preview(_), _) => None,
+        };
+        self.live_preview.tick(highlighted, &self.events);
+    }
 
-    /// Set running to false to quit the application.
-    pub fn quit(&mut self) {
-        self.running = false;
+    /// Shows the error in a popup, which can retry the action that failed if any
+    pub fn error(&mut self, error: anyhow::Error, retry: Option<UIRetry>) {
+        let message = format!("{error:#}");
+        self.errors.push(UIError { message, retry });
+    }
+
+    /// Dismisses the error popup shown, retrying the action that failed if requested
+    pub fn error_dismiss(&mut self, retry: bool) {
+        if self.errors.is_empty() {
+            return;
+        }
+        let error = self.errors.remove(0);
+        match error.retry {
+            Some(UIRetry::Collection(kind)) if retry => self.collection_reload(&kind),
+            Some(UIRetry::Preview(templates)) if retry => self.preview(templates),
+            _ => {}
+        }
+    }
+
+    /// Shows the templates of a collection once loaded in the background
+    pub fn collection_loaded(
+        &mut self,
+        kind: TemplateCollectionKind,
+        items: Result<Vec<UICollectionItem>>,
+    ) {
+        let index = match self.collection_index(&kind) {
+            Some(index) => index,
+            None => return,
+        };
+        match items {
+            Ok(items) => {
+                let mut collection = self.collections[index].lock().unwrap();
+                collection.status = UICollectionStatus::Loaded;
+                collection.set_items(items, self.collection_filter.value());
+            }
+            // Collections that can't be loaded when the user chose to be offline are skipped,
+            // as retrying won't help
+            Err(_) if get_cli().offline => {
+                note(&format!(
+                    "Skipped {} templates, which are not cached",
+                    kind.name()
+                ));
+                self.collections.remove(index);
+                if self.collection_tab >= index {
+                    self.collection_tab = self.collection_tab.saturating_sub(1);
+                }
+            }
+            Err(e) => {
+                let message = format!("Failed to load collection: {}\n{e:#}", kind.name());
+                self.collections[index].lock().unwrap().status =
+                    UICollectionStatus::Failed(message.clone());
+                let retry = Some(UIRetry::Collection(kind));
+                self.errors.push(UIError { message, retry });
+            }
+        }
+        self.collection_all_update();
+    }
+
+    /// Loads the templates of the collection again
+    pub fn collection_reload(&mut self, kind: &TemplateCollectionKind) {
+        if let Some(index) = self.collection_index(kind) {
+            self.collections[index].lock().unwrap().status = UICollectionStatus::Loading;
+            load_collection(kind.clone(), &self.events);
+            self.collection_all_update();
+        }
+    }
+
+    /// Loads the templates of the collections that failed to load in the current tab again,
+    /// which are all of them on the "All" tab
+    pub fn collection_retry(&mut self) {
+        let is_all = self.collection().is_all;
+        let failed = self
+            .collections
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| is_all || *i == self.collection_tab)
+            .map(|(_, c)| c.lock().unwrap())
+            .filter(|c| matches!(c.status, UICollectionStatus::Failed(_)) && !c.is_all)
+            .map(|c| c.kind.clone())
+            .collect::<Vec<_>>();
+        for kind in failed.iter() {
+            self.collection_reload(kind);
+        }
+    }
+
+    /// Returns the index of the collection tab
+    fn collection_index(&self, kind: &TemplateCollectionKind) -> Option<usize> {
+        self.collections.iter().position(|c| {
+            let c = c.lock().unwrap();
+            !c.is_all && &c.kind == kind
+        })
     }
 
-    /// Returns a list of tab titles for the template collections
-    pub fn collection_tab_titles(&self) -> Vec<String> {
-        self.collections
+    /// Updates the "All" tab with the templates of the collections loaded so far, it's loading
+    /// while any of them are
+    fn collection_all_update(&mut self) {
+        let (all, collections) = self.collections.split_at(1);
+        let mut all = all[0].lock().unwrap();
+        all.set_items(
+            UICollection::merge(collections),
+            self.collection_filter.value(),
+        );
+        all.status = match collections
             .iter()
-            .map(|list| list.lock().unwrap().collection.kind.name().to_string())
-            .collect()
+            .any(|c| c.lock().unwrap().status == UICollectionStatus::Loading)
+        {
+            true => UICollectionStatus::Loading,
+            false => UICollectionStatus::Loaded,
+        };
+    }
+
+    /// Loads the preview of the templates in the background, showing it once loaded
+    pub fn preview(&mut self, templates: TemplateList) {
+        load_preview(templates.clone(), &self.events);
+        self.preview_loading = Some(templates);
+    }
+
+    /// Shows the preview of the templates once loaded in the background, unless it was
+    /// cancelled in the meantime. Returns whether the view changed.
+    pub fn preview_loaded(
+        &mut self,
+        templates: TemplateList,
+        preview: Result<UIStatePreview>,
+    ) -> bool {
+        match &self.preview_loading {
+            Some(loading) if loading.items == templates.items => self.preview_loading = None,
+            _ => return false,
+        }
+        match preview {
+            Ok(preview) => {
+                self.view = UIStateView::Preview(preview);
+                true
+            }
+            Err(e) => {
+                self.error(e, Some(UIRetry::Preview(templates)));
+                false
+            }
+        }
+    }
+
+    /// Set running to false to quit the application.
+    pub fn quit(&mut self) {
+        self.running = false;
     }
 
     /// Goes to the next template collection tab on the right
@@ -108,16 +298,24 @@ impl UIState {
         list.previous(inc);
     }
 
+    /// Returns the highlighted template in the template collection list
+    pub fn list_highlighted(&self) -> Option<Template> {
+        let list = self.collection();
+        let index = list.state.lock().unwrap().selected()?;
+        list.values.get(index).map(|item| item.template.clone())
+    }
+
     /// Selects an item in the template collection list
-    pub fn list_select(&self) {
+    pub fn list_select(&mut self) {
         let list = self.collection();
-        let state = list.state.lock().unwrap();
-        let index = state.selected().unwrap_or(0);
-        let template = list.values.get(index);
-        if let Some(template) = template {
+        let index = list.state.lock().unwrap().selected().unwrap_or(0);
+        let item = list.values.get(index).cloned();
+        drop(list);
+
+        if let Some(item) = item {
             let selection = UICollectionSelection {
-                kind: list.collection.kind.clone(),
-                template: template.clone(),
+                kind: item.kind,
+                template: item.template,
             };
 
             let mut selected = self.selected.lock().unwrap();
@@ -131,24 +329,116 @@ impl UIState {
                 }
             }
         }
+        self.selected_update();
+    }
+
+    /// Moves the keyboard input between the template collection list and the selected
+    /// templates list, which can only be focused when templates are selected
+    pub fn focus_toggle(&mut self) {
+        self.focus = match self.focus {
+            UIFocus::List if !self.selected.lock().unwrap().is_empty() => UIFocus::Selection,
+            _ => UIFocus::List,
+        };
+        if self.selected_state.selected().is_none() {
+            self.selected_state.select(Some(0));
+        }
+    }
+
+    /// Returns the selected templates in order
+    pub fn selected_templates(&self) -> TemplateList {
+        let selected = self.selected.lock().unwrap();
+        TemplateList::new(selected.iter().map(|s| s.template.clone()).collect())
+    }
+
+    /// Returns the highlighted template in the selected templates list
+    pub fn selected_highlighted(&self) -> Option<Template> {
+        let index = self.selected_state.selected()?;
+        let selected = self.selected.lock().unwrap();
+        selected.get(index).map(|s| s.template.clone())
+    }
+
+    /// Moves to the next item in the selected templates list
+    pub fn selected_next(&mut self) {
+        let length = self.selected.lock().unwrap().len();
+        if length > 0 {
+            let i = self
+                .selected_state
+                .selected()
+                .map_or(0, |i| (i + 1) % length);
+            self.selected_state.select(Some(i));
+        }
+    }
+
+    /// Moves to the previous item in the selected templates list
+    pub fn selected_previous(&mut self) {
+        let length = self.selected.lock().unwrap().len();
+        if length > 0 {
+            let i = self
+                .selected_state
+                .selected()
+                .map_or(0, |i| (i + length - 1) % length);
+            self.selected_state.select(Some(i));
+        }
+    }
+
+    /// Moves the highlighted template up or down in the selected templates list, changing the
+    /// order the templates are generated in
+    pub fn selected_move(&mut self, up: bool) {
+        let index = self.selected_state.selected().unwrap_or(0);
+        let target = match up {
+            true => index.checked_sub(1),
+            false => index.checked_add(1),
+        };
+        {
+            let mut selected = self.selected.lock().unwrap();
+            match target {
+                Some(target) if target < selected.len() => selected.swap(index, target),
+                _ => return,
+            }
+        }
+        self.selected_state.select(target);
+        self.selected_update();
+    }
+
+    /// Removes the highlighted template from the selected templates list
+    pub fn selected_remove(&mut self) {
+        let index = self.selected_state.selected().unwrap_or(0);
+        {
+            let mut selected = self.selected.lock().unwrap();
+            if index < selected.len() {
+                selected.remove(index);
+            }
+        }
+        self.selected_update();
+    }
+
+    /// Removes all the templates from the selected templates list
+    pub fn selected_clear(&mut self) {
+        self.selected.lock().unwrap().clear();
+        self.selected_update();
+    }
+
+    /// Updates the command and list state after the selected templates changed, moving the
+    /// focus back to the template collection list once none are left
+    fn selected_update(&mut self) {
+        let templates = self.selected_templates();
+        self.selected_command = match templates.is_empty() {
+            true => String::new(),
+            false => templates.command().unwrap_or_default(),
+        };
+
+        let index = self.selected_state.selected().unwrap_or(0);
+        self.selected_state
+            .select(Some(index.min(templates.len().saturating_sub(1))));
+        if templates.is_empty() {
+            self.focus = UIFocus::List;
+        }
     }
 
     /// Filters item in the template collection list based on the filter input
     pub fn list_filter_update(&self) {
         let mut list = self.collection();
-        let value = self.collection_filter.value();
-
-        list.values = if value.is_empty() {
-            list.collection.items.clone()
-        } else {
-            let value = value.to_lowercase();
-            list.collection
-                .items
-                .iter()
-                .filter(|v| v.value.name().unwrap().to_lowercase().contains(&value))
-                .cloned()
-                .collect()
-        }
+        list.filter(self.collection_filter.value());
     }
 
     /// Returns true if the template collection list is filtering item
@@ -1,9 +1,15 @@
+use crate::commands::create::{render_output, repository, write_output};
+use crate::config::manifest::Manifest;
 use crate::template::list::TemplateList;
-use anyhow::{anyhow, Result};
+use crate::util::diff::{diff_lines, DiffLine};
+use anyhow::{anyhow, Context, Result};
 use copypasta::{ClipboardContext, ClipboardProvider};
 use once_cell::sync::Lazy;
 use ratatui::widgets::ScrollbarState;
+use std::path::PathBuf;
 use std::sync::Mutex;
+use std::{env, fs};
+use tui_input::Input;
 
 static CLIPBOARD: Lazy<Mutex<ClipboardContext>> =
     Lazy::new(|| Mutex::new(ClipboardContext::new().unwrap()));
@@ -16,6 +22,16 @@ pub enum UIStatePreviewState {
     CopiedContent,
     /// Triggered when user copies the CLI command to the clipboard
     CopiedCommand,
+    /// Triggered when user writes the templates to the project manifest
+    SavedManifest,
+    /// Prompting the user for the file path to write the preview content to
+    PromptPath(Input),
+    /// Asking the user whether to overwrite the existing file at the path
+    ConfirmOverwrite(PathBuf),
+    /// Triggered when user writes the preview content to the file at the path
+    Saved(PathBuf),
+    /// Triggered when writing the preview content fails, with the error message
+    SaveFailed(String),
 }
 
 /// State of the preview view
@@ -37,6 +53,9 @@ pub struct UIStatePreview {
     pub content_lines: u16,
     /// The CLI command used to generate the preview content
     pub command: String,
+    /// The diff between the .gitignore file in the current directory and the preview content,
+    /// shown instead of the content when toggled on
+    pub diff: Option<Vec<DiffLine>>,
 }
 
 impl UIStatePreview {
@@ -66,9 +85,34 @@ impl UIStatePreview {
             content_lines,
             command,
             templates,
+            diff: None,
         })
     }
 
+    /// Toggles between showing the preview content and its diff against the .gitignore file
+    /// in the current directory
+    pub fn toggle_diff(&mut self) -> Result<()> {
+        self.diff = match self.diff {
+            Some(_) => None,
+            None => {
+                let cwd = env::current_dir().with_context(|| "Failed to get current directory")?;
+                let path = cwd.join(".gitignore");
+                let current = fs::read_to_string(&path).unwrap_or_default();
+                let new = render_output(&path, &self.content, false)?;
+                Some(diff_lines(&current, &new, ".gitignore", "preview"))
+            }
+        };
+
+        let lines = match &self.diff {
+            Some(diff) => diff.len().max(1),
+            None => self.content.matches('\n').count() + 1,
+        };
+        self.content_lines = lines as u16;
+        self.scroll_state = self.scroll_state.content_length(lines);
+        self.scroll_to_top();
+        Ok(())
+    }
+
     /// Scrolls to the top of the preview content
     pub fn scroll_to_top(&mut self) {
         self.scroll_pos = 0;
@@ -110,6 +154,102 @@ impl UIStatePreview {
         Ok(())
     }
 
+    /// Writes the previewed templates to the manifest file in the current directory
+    pub fn save_manifest(&mut self) -> Result<()> {
+        let path = Manifest::default_path()?;
+        Manifest::save_templates(&path, &self.templates)?;
+        self.state = UIStatePreviewState::SavedManifest;
+        Ok(())
+    }
+
+    /// Writes the preview content to the .gitignore file in the current directory, asking for
+    /// confirmation first if it already exists
+    pub fn save_gitignore(&mut self) -> Result<()> {
+        let cwd = env::current_dir().with_context(|| "Failed to get current directory")?;
+        self.save_path(cwd.join(".gitignore"));
+        Ok(())
+    }
+
+    /// Merges the preview content into the managed block of the .gitignore file in the current
+    /// directory, keeping any content outside of it
+    pub fn save_block(&mut self) -> Result<()> {
+        let cwd = env::current_dir().with_context(|| "Failed to get current directory")?;
+        self.write(cwd.join(".gitignore"), true);
+        Ok(())
+    }
+
+    /// Merges the preview content into the managed block of the exclude file of the git
+    /// repository in the current directory
+    pub fn save_exclude(&mut self) -> Result<()> {
+        let cwd = env::current_dir().with_context(|| "Failed to get current directory")?;
+        match repository(&cwd) {
+            Ok(repository) => self.write(repository.exclude_file(), true),
+            Err(e) => self.state = UIStatePreviewState::SaveFailed(format!("{e:#}")),
+        }
+        Ok(())
+    }
+
+    /// Prompts the user for the file path to write the preview content to
+    pub fn prompt_path(&mut self) {
+        let input = Input::default().with_value(".gitignore".to_string());
+        self.state = UIStatePreviewState::PromptPath(input);
+    }
+
+    /// Writes the preview content to the path entered in the prompt, relative to the current
+    /// directory
+    pub fn submit_path(&mut self) -> Result<()> {
+        let input = match &self.state {
+            UIStatePreviewState::PromptPath(input) => input.value().trim().to_string(),
+            _ => return Ok(()),
+        };
+        if input.is_empty() {
+            return Ok(());
+        }
+
+        let cwd = env::current_dir().with_context(|| "Failed to get current directory")?;
+        let path = cwd.join(input);
+        if path.is_dir() {
+            let path = path.to_str().unwrap_or("...unknown path");
+            let error = format!("The path is a directory, provide a file path instead: {path}");
+            self.state = UIStatePreviewState::SaveFailed(error);
+            return Ok(());
+        }
+        self.save_path(path);
+        Ok(())
+    }
+
+    /// Overwrites the file the user confirmed to overwrite
+    pub fn confirm_overwrite(&mut self) {
+        if let UIStatePreviewState::ConfirmOverwrite(path) = &self.state {
+            self.write(path.clone(), false);
+        }
+    }
+
+    /// Checks if a dialog waiting for user input is shown
+    pub fn is_dialog(&self) -> bool {
+        matches!(
+            self.state,
+            UIStatePreviewState::PromptPath(_) | UIStatePreviewState::ConfirmOverwrite(_)
+        )
+    }
+
+    /// Writes the preview content to the path, asking for confirmation if the file exists
+    fn save_path(&mut self, path: PathBuf) {
+        if path.exists() {
+            self.state = UIStatePreviewState::ConfirmOverwrite(path);
+        } else {
+            self.write(path, false);
+        }
+    }
+
+    /// Writes the preview content to the path, showing the result in a popup
+    fn write(&mut self, path: PathBuf, merge: bool) {
+        self.state = match write_output(&path, &self.content, merge) {
+            Ok(_) => UIStatePreviewState::Saved(path),
+            Err(e) => UIStatePreviewState::SaveFailed(format!("{e:#}")),
+        };
+    }
+
     /// Copies the given content to the clipboard
     fn copy_to_clipboard(&self, content: String) -> Result<()> {
         let mut clip = CLIPBOARD
@@ -1,4 +1,4 @@
-use crate::commands::search::state::UIState;
+use crate::commands::search::state::{UIFocus, UIState};
 use indoc::formatdoc;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Style, Stylize};
@@ -6,7 +6,7 @@ use ratatui::widgets::{Block, Padding, Paragraph};
 use ratatui::{text, Frame};
 
 /// Renders the home UI footer (help section)
-pub fn render_home_footer(_app: &mut UIState, f: &mut Frame, chunk: Rect) -> anyhow::Result<()> {
+pub fn render_home_footer(app: &mut UIState, f: &mut Frame, chunk: Rect) -> anyhow::Result<()> {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
@@ -14,30 +14,54 @@ pub fn render_home_footer(_app: &mut UIState, f: &mut Frame, chunk: Rect) -> any
             Constraint::Length(38),
             Constraint::Length(26),
             Constraint::Length(26),
+            Constraint::Length(26),
         ])
         .margin(0)
         .horizontal_margin(1)
         .split(chunk);
 
     let text1 = formatdoc! {"
-        Quit: Ctrl + C
-    "};
-
-    let text2 = formatdoc! {"
-        Tabs: 🠜 🠞
-        List: 🠝 🠟 or M. Wheel (+Shift=fast)
+        Quit:  Ctrl + C
+        Retry: Shift + R
     "};
 
-    let text3 = formatdoc! {"
-        Select: Enter
-        Filter: Start typing
-    "};
+    // The keys of the templates list, or of the selection list while it's focused
+    let (title2, text2, text3) = match app.focus {
+        UIFocus::List => (
+            "Templates",
+            formatdoc! {"
+                Tabs: 🠜 🠞
+                List: 🠝 🠟 or M. Wheel (+Shift=fast)
+            "},
+            formatdoc! {"
+                Select: Enter
+                Filter: Start typing
+                Selection: Tab
+            "},
+        ),
+        UIFocus::Selection => (
+            "Selection",
+            formatdoc! {"
+                List: 🠝 🠟
+                Move: Shift + 🠝 🠟
+            "},
+            formatdoc! {"
+                Remove: Delete
+                Clear:  Shift + X
+                Back:   Tab or Esc
+            "},
+        ),
+    };
 
     let text4 = formatdoc! {"
         Current:   Shift + C
         Selection: Shift + S
     "};
 
+    let text5 = formatdoc! {"
+        Live Pane: Shift + P
+    "};
+
     let block = Block::default()
         .padding(Padding::new(0, 1, 0, 0))
         .dark_gray();
@@ -47,7 +71,7 @@ pub fn render_home_footer(_app: &mut UIState, f: &mut Frame, chunk: Rect) -> any
         .style(Style::default())
         .block(block.clone().title(t1));
 
-    let t2 = text::Span::from("Templates").bold().underlined();
+    let t2 = text::Span::from(title2).bold().underlined();
     let p2 = Paragraph::new(text2)
         .style(Style::default())
         .block(block.clone().title(t2));
@@ -62,10 +86,16 @@ pub fn render_home_footer(_app: &mut UIState, f: &mut Frame, chunk: Rect) -> any
         .style(Style::default())
         .block(block.clone().title(t4));
 
+    let t5 = text::Span::from("Preview").bold().underlined();
+    let p5 = Paragraph::new(text5)
+        .style(Style::default())
+        .block(block.clone().title(t5));
+
     f.render_widget(p1, chunks[0]);
     f.render_widget(p2, chunks[1]);
     f.render_widget(p3, chunks[2]);
     f.render_widget(p4, chunks[3]);
+    f.render_widget(p5, chunks[4]);
 
     Ok(())
 }
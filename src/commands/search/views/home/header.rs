@@ -1,16 +1,32 @@
+use crate::commands::search::state::collection::UICollectionStatus;
 use crate::commands::search::state::UIState;
+use crate::commands::search::views::util;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::symbols::DOT;
-use ratatui::text::Line;
+use ratatui::text::{Line, Span};
 use ratatui::widgets::{canvas, Block, BorderType, Borders, Padding, Tabs};
 use ratatui::{text, Frame};
 
 /// Create the tabs widget
-fn create_tabs<'a>(tab_titles: Vec<String>, list_tab: usize) -> anyhow::Result<Tabs<'a>> {
-    let items = tab_titles
-        .into_iter()
-        .map(|t| Line::from(format!(" {t} ")))
+fn create_tabs<'a>(app: &UIState) -> anyhow::Result<Tabs<'a>> {
+    // Show whether each collection is still loading or failed to load after its name
+    let items = app
+        .collections
+        .iter()
+        .map(|collection| {
+            let collection = collection.lock().unwrap();
+            let mut line = Line::from(format!(" {} ", collection.kind.name()));
+            match &collection.status {
+                UICollectionStatus::Loaded => {}
+                UICollectionStatus::Loading => {
+                    let frame = util::spinner(app.ticks);
+                    line.push_span(Span::from(format!("{frame} ")).light_yellow());
+                }
+                UICollectionStatus::Failed(_) => line.push_span(Span::from("✗ ").light_red()),
+            }
+            line
+        })
         .collect::<Vec<Line>>();
     let block = Block::default()
         .borders(Borders::ALL)
@@ -20,7 +36,7 @@ fn create_tabs<'a>(tab_titles: Vec<String>, list_tab: usize) -> anyhow::Result<T
         .block(block)
         .style(Style::default().fg(Color::White).bold())
         .highlight_style(Style::default().black().on_light_yellow().bold())
-        .select(list_tab)
+        .select(app.collection_tab)
         .divider(DOT);
     Ok(tabs)
 }
@@ -36,7 +52,7 @@ pub fn render_home_header(app: &mut UIState, f: &mut Frame, chunk: Rect) -> anyh
         ])
         .split(chunk);
 
-    let tabs = create_tabs(app.collection_tab_titles(), app.collection_tab)?;
+    let tabs = create_tabs(app)?;
 
     let logo = canvas::Canvas::default()
         .block(
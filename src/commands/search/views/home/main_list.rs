@@ -1,41 +1,58 @@
-use crate::commands::search::state::collection::UICollectionSelection;
+use crate::commands::search::state::collection::{
+    UICollection, UICollectionItem, UICollectionSelection, UICollectionStatus,
+};
 use crate::commands::search::state::UIState;
 use crate::commands::search::views::util;
-use crate::template::item::Template;
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style, Stylize};
-use ratatui::widgets::{Block, BorderType, Borders, List, ListItem, ListState, Padding};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{
+    Block, BorderType, Borders, List, ListItem, ListState, Padding, Paragraph, Wrap,
+};
 use ratatui::Frame;
 use std::sync::MutexGuard;
 
 /// Create the templates list widget
 fn create_list<'a>(
-    values: &[Template],
+    list: &UICollection,
     state: &mut MutexGuard<ListState>,
     selected: &MutexGuard<Vec<UICollectionSelection>>,
+    ticks: usize,
 ) -> anyhow::Result<List<'a>> {
+    let values = &list.values;
     let index = state.selected().unwrap_or(0) + 1;
+    let name_width = values
+        .iter()
+        .map(|v| v.name.chars().count())
+        .max()
+        .unwrap_or(0);
 
     let items: Vec<ListItem> = values
         .iter()
-        .map(|tmpl| {
-            let name = tmpl.value.name().unwrap();
-            let item = ListItem::new(name);
-            match selected.iter().position(|s| &s.template == tmpl) {
+        .map(|value| {
+            let mut line = highlighted_name(value);
+            // Show which collection the template is from when listing all of them
+            if list.is_all {
+                let padding = name_width - value.name.chars().count() + 2;
+                line.push_span(Span::from(" ".repeat(padding)));
+                line.push_span(Span::from(value.kind.name().to_string()).dark_gray());
+            }
+            let item = ListItem::new(line);
+            match selected.iter().position(|s| s.template == value.template) {
                 Some(_) => item.style(Style::default().bold().on_dark_gray()),
                 None => item,
             }
         })
         .collect();
 
-    let title = format!("List ({}/{})", index, values.len());
-    let title = util::title_string(title);
-
-    let block = Block::default()
-        .title(title)
-        .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
-        .padding(Padding::new(1, 1, 0, 0));
+    // The "All" list is still loading while any of the collections are
+    let title = match list.status {
+        UICollectionStatus::Loading => {
+            format!("List ({}/{}) {}", index, values.len(), util::spinner(ticks))
+        }
+        _ => format!("List ({}/{})", index, values.len()),
+    };
+    let block = list_block(util::title_string(title));
 
     let widget = List::new(items)
         .block(block)
@@ -45,11 +62,59 @@ fn create_list<'a>(
     Ok(widget)
 }
 
+fn list_block<'a>(title: String) -> Block<'a> {
+    Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .padding(Padding::new(1, 1, 0, 0))
+}
+
 /// Renders the main collection templates list
 pub fn render_home_main_list(app: &mut UIState, f: &mut Frame, chunk: Rect) -> anyhow::Result<()> {
     let list = app.collection();
+
+    // Show why the list is empty while the collection is loading or if it failed to load
+    let text = match &list.status {
+        _ if !list.items.is_empty() => None,
+        UICollectionStatus::Loaded => None,
+        UICollectionStatus::Loading => {
+            let frame = util::spinner(app.ticks);
+            Some(Text::from(
+                Span::from(format!("{frame} Loading templates...")).dark_gray(),
+            ))
+        }
+        UICollectionStatus::Failed(error) => {
+            let mut lines = error
+                .lines()
+                .map(|line| Line::from(line.to_string()).light_red())
+                .collect::<Vec<_>>();
+            lines.push(Line::from(""));
+            lines.push(Line::from("Retry: Shift + R").dark_gray());
+            Some(Text::from(lines))
+        }
+    };
+    if let Some(text) = text {
+        let block = list_block(util::title_string("List".to_string()));
+        let widget = Paragraph::new(text).wrap(Wrap { trim: false }).block(block);
+        f.render_widget(widget, chunk);
+        return Ok(());
+    }
+
     let state = &mut list.state.lock().unwrap();
-    let widget = create_list(&list.values, state, &app.selected.lock().unwrap())?;
+    let widget = create_list(&list, state, &app.selected.lock().unwrap(), app.ticks)?;
     f.render_stateful_widget(widget, chunk, state);
     Ok(())
 }
+
+/// Returns the template name with the characters matching the filter highlighted
+fn highlighted_name<'a>(value: &UICollectionItem) -> Line<'a> {
+    let spans = value.name.chars().enumerate().map(|(i, c)| {
+        let span = Span::from(c.to_string());
+        match value.matches.contains(&i) {
+            true => span.light_yellow().bold(),
+            false => span,
+        }
+    });
+    Line::from(spans.collect::<Vec<_>>())
+}
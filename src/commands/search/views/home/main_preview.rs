@@ -0,0 +1,47 @@
+use crate::commands::search::state::live_preview::UILivePreviewContent;
+use crate::commands::search::state::UIState;
+use crate::commands::search::views::util;
+use ratatui::layout::Rect;
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Span;
+use ratatui::widgets::{Block, BorderType, Borders, Padding, Paragraph, Wrap};
+use ratatui::Frame;
+
+/// Renders the live preview pane of the highlighted template
+pub fn render_home_main_preview(
+    app: &mut UIState,
+    f: &mut Frame,
+    chunk: Rect,
+) -> anyhow::Result<()> {
+    let preview = &app.live_preview;
+    let name = match &preview.template {
+        Some(template) => template.value.name()?,
+        None => String::new(),
+    };
+    let title = match name.is_empty() {
+        true => util::title_string("Preview".into()),
+        false => util::title_string(format!("Preview: {name}")),
+    };
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::new().light_yellow())
+        .padding(Padding::horizontal(1));
+
+    let content = match &preview.content {
+        UILivePreviewContent::Empty => {
+            Paragraph::new(Span::from("No template highlighted").dark_gray())
+        }
+        UILivePreviewContent::Loading => {
+            let frame = util::spinner(app.ticks);
+            Paragraph::new(Span::from(format!("{frame} Loading template...")).dark_gray())
+        }
+        UILivePreviewContent::Loaded(content) => Paragraph::new(content.as_str()),
+        UILivePreviewContent::Failed(error) => Paragraph::new(error.as_str()).light_red(),
+    };
+
+    f.render_widget(content.wrap(Wrap { trim: false }).block(block), chunk);
+    Ok(())
+}
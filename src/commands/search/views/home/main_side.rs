@@ -1,10 +1,11 @@
 use crate::commands::search::state::collection::UICollectionSelection;
-use crate::commands::search::state::UIState;
+use crate::commands::search::state::{UIFocus, UIState};
 use crate::commands::search::views::util;
 use ratatui::layout::{Constraint, Layout, Position, Rect};
-use ratatui::style::{Color, Style, Stylize};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
 use ratatui::widgets::block::Title;
-use ratatui::widgets::{Block, BorderType, Borders, List, ListItem, Padding, Paragraph};
+use ratatui::widgets::{Block, BorderType, Borders, List, ListItem, Padding, Paragraph, Wrap};
 use ratatui::Frame;
 use std::sync::MutexGuard;
 
@@ -30,13 +31,19 @@ fn create_filter_input(app: &mut UIState) -> anyhow::Result<Paragraph<'_>> {
 /// Create the selected templates list widget
 fn create_selected<'a>(
     selected: &MutexGuard<Vec<UICollectionSelection>>,
+    is_focused: bool,
 ) -> anyhow::Result<List<'a>> {
+    // Number the templates as they're generated in that order
     let items: Vec<ListItem> = selected
         .iter()
-        .map(|s| {
+        .enumerate()
+        .map(|(i, s)| {
             let name = s.template.value.name().unwrap();
             let prefix = s.kind.name();
-            ListItem::new(format!("{prefix} - {name}"))
+            ListItem::new(Line::from(vec![
+                Span::from(format!("{}. ", i + 1)).dark_gray(),
+                Span::from(format!("{prefix} - {name}")),
+            ]))
         })
         .collect();
 
@@ -44,39 +51,76 @@ fn create_selected<'a>(
     let title = util::title_string(title);
     let title = Title::from(title);
 
-    let block = Block::default()
+    let mut block = Block::default()
         .title(title)
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .padding(Padding::horizontal(1));
+    if is_focused {
+        block = block.border_style(Style::default().light_yellow());
+    }
 
     let widget = List::new(items)
         .block(block)
-        .style(Style::default().fg(Color::White));
+        .style(Style::default().fg(Color::White))
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol("▷ ");
 
     Ok(widget)
 }
 
-/// Render the right sidebar of the home view (filter input & selected templates)
+/// Create the widget showing the CLI command generating the selected templates
+fn create_command(command: &str) -> Paragraph<'_> {
+    let title = util::title_string("Command".into());
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .padding(Padding::horizontal(1));
+    Paragraph::new(command)
+        .dark_gray()
+        .wrap(Wrap { trim: false })
+        .block(block)
+}
+
+/// Render the right sidebar of the home view (filter input, selected templates & command)
 pub fn render_home_main_side(app: &mut UIState, f: &mut Frame, chunk: Rect) -> anyhow::Result<()> {
+    // The command is shown below the selected templates, sized to fit up to 6 wrapped lines
+    let width = chunk.width.saturating_sub(4) as usize;
+    let command_height = match app.selected_command.is_empty() {
+        true => 0,
+        false => wrapped_lines(&app.selected_command, width).min(6) as u16 + 2,
+    };
     let chunks = Layout::default()
-        .constraints(vec![Constraint::Length(3), Constraint::Min(0)])
+        .constraints(vec![
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(command_height),
+        ])
         .split(chunk);
 
     let top = chunks[0];
     let bottom = chunks[1];
 
-    // Create selected list
-    let selected_widget = create_selected(&app.selected.lock().unwrap())?;
+    // Create selected list, highlighting the current template only while it's focused
+    let is_focused = app.focus == UIFocus::Selection;
+    let selected_widget = create_selected(&app.selected.lock().unwrap(), is_focused)?;
 
     // Create filter input widget
     let is_filtering = app.list_is_filtering();
     let filter_widget = create_filter_input(app)?;
 
     f.render_widget(filter_widget, top);
-    f.render_widget(selected_widget, bottom);
+    if is_focused {
+        f.render_stateful_widget(selected_widget, bottom, &mut app.selected_state);
+    } else {
+        f.render_widget(selected_widget, bottom);
+    }
+    if command_height > 0 {
+        f.render_widget(create_command(&app.selected_command), chunks[2]);
+    }
 
-    if is_filtering {
+    if is_filtering && !is_focused {
         f.set_cursor_position(Position::new(
             // Put cursor past the end of the input text
             top.x + app.collection_filter.visual_cursor() as u16 + 2,
@@ -87,3 +131,26 @@ pub fn render_home_main_side(app: &mut UIState, f: &mut Frame, chunk: Rect) -> a
 
     Ok(())
 }
+
+/// Returns the number of lines the text takes up when wrapped at word boundaries
+fn wrapped_lines(text: &str, width: usize) -> usize {
+    let mut lines = 1;
+    let mut line_width = 0;
+    for word in text.split_whitespace() {
+        let word_width = word.chars().count();
+        if line_width > 0 && line_width + 1 + word_width > width {
+            lines += 1;
+            line_width = 0;
+        }
+        if line_width > 0 {
+            line_width += 1;
+        }
+        // Words longer than the line are broken up
+        line_width += word_width;
+        while line_width > width && width > 0 {
+            lines += 1;
+            line_width -= width;
+        }
+    }
+    lines
+}
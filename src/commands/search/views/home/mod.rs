@@ -1,12 +1,14 @@
 mod footer;
 mod header;
 mod main_list;
+mod main_preview;
 mod main_side;
 
 use crate::commands::search::state::{UIState, UIStateView};
 use crate::commands::search::views::home::footer::render_home_footer;
 use crate::commands::search::views::home::header::render_home_header;
 use crate::commands::search::views::home::main_list::render_home_main_list;
+use crate::commands::search::views::home::main_preview::render_home_main_preview;
 use crate::commands::search::views::home::main_side::render_home_main_side;
 use anyhow::{bail, Result};
 use ratatui::layout::{Constraint, Direction, Layout};
@@ -22,28 +24,45 @@ pub fn render_home(app: &mut UIState, f: &mut Frame) -> Result<()> {
                     Constraint::Length(3),
                     Constraint::Length(0),
                     Constraint::Min(0),
-                    Constraint::Length(3),
+                    Constraint::Length(4),
                 ])
                 .vertical_margin(0)
                 .horizontal_margin(1)
                 .split(f.area());
 
-            let main = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints(vec![
-                    Constraint::Min(40),
-                    Constraint::Length(1),
-                    Constraint::Length(40),
-                ])
-                .split(layout[2]);
+            // The live preview pane is shown between the list and the sidebar when enabled
+            let main = if app.live_preview.enabled {
+                Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints(vec![
+                        Constraint::Min(30),
+                        Constraint::Length(1),
+                        Constraint::Percentage(45),
+                        Constraint::Length(1),
+                        Constraint::Length(40),
+                    ])
+                    .split(layout[2])
+            } else {
+                Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints(vec![
+                        Constraint::Min(40),
+                        Constraint::Length(1),
+                        Constraint::Length(40),
+                    ])
+                    .split(layout[2])
+            };
 
             let header = layout[0];
             let main_left = main[0];
-            let main_right = main[2];
+            let main_right = main[main.len() - 1];
             let footer = layout[3];
 
             render_home_header(app, f, header)?;
             render_home_main_list(app, f, main_left)?;
+            if app.live_preview.enabled {
+                render_home_main_preview(app, f, main[2])?;
+            }
             render_home_main_side(app, f, main_right)?;
             render_home_footer(app, f, footer)?;
         }
@@ -1,4 +1,5 @@
 mod home;
+mod popup;
 mod preview;
 mod util;
 
@@ -11,5 +12,6 @@ pub fn render(app: &mut UIState, f: &mut Frame) -> anyhow::Result<()> {
         UIStateView::Home => home::render_home(app, f)?,
         UIStateView::Preview { .. } => preview::render_preview(app, f)?,
     }
+    popup::render_popups(app, f);
     Ok(())
 }
@@ -0,0 +1,55 @@
+use crate::commands::search::state::UIState;
+use crate::commands::search::views::util::{render_popup, spinner};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, BorderType, Borders, Padding};
+use ratatui::Frame;
+
+/// Renders the popups shown over any view, for loading the preview and for errors
+pub fn render_popups(app: &mut UIState, f: &mut Frame) {
+    if let Some(templates) = &app.preview_loading {
+        let popup_block = Block::default()
+            .title("─ Preview ─")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::new().light_yellow())
+            .padding(Padding::new(2, 1, 0, 0));
+
+        let message = match templates.len() {
+            1 => "Loading template...".to_string(),
+            n => format!("Loading {n} templates..."),
+        };
+        let popup_text = Text::from(vec![
+            Line::from(format!("{} {message}", spinner(app.ticks))),
+            Line::from(Span::from("Esc: Cancel").italic().dark_gray()),
+        ]);
+        render_popup(f, popup_block, popup_text);
+    }
+
+    // Errors are shown one at a time, in the order they happened
+    if let Some(error) = app.errors.first() {
+        let title = match app.errors.len() {
+            1 => "─ Error ─".to_string(),
+            n => format!("─ Error (1/{n}) ─"),
+        };
+        let popup_block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::new().light_red())
+            .padding(Padding::new(2, 1, 0, 0));
+
+        let hint = match error.retry {
+            Some(_) => "r: Retry  Any other key: Dismiss",
+            None => "Any key: Dismiss",
+        };
+        let mut lines = error
+            .message
+            .lines()
+            .map(|line| Line::from(line.to_string()))
+            .collect::<Vec<_>>();
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::from(hint).italic().dark_gray()));
+        render_popup(f, popup_block, Text::from(lines));
+    }
+}
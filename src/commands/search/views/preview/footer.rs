@@ -9,9 +9,12 @@ pub fn render_preview_footer(f: &mut Frame, chunk: Rect) -> anyhow::Result<()> {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Min(20),
-            Constraint::Min(20),
-            Constraint::Min(50),
+            Constraint::Min(18),
+            Constraint::Min(18),
+            Constraint::Min(26),
+            Constraint::Min(24),
+            Constraint::Min(22),
+            Constraint::Min(24),
         ])
         .margin(0)
         .horizontal_margin(1)
@@ -30,6 +33,23 @@ pub fn render_preview_footer(f: &mut Frame, chunk: Rect) -> anyhow::Result<()> {
     let text3 = formatdoc! {"
         Copy Template: Shift + C
         Copy Command:  Shift + X
+        Diff:          Shift + D
+    "};
+
+    let text4 = formatdoc! {"
+        .gitignore: Shift + W
+        Save As:    Shift + F
+        Manifest:   Shift + M
+    "};
+
+    let text5 = formatdoc! {"
+        Block:   Shift + B
+        Exclude: Shift + E
+    "};
+
+    let text6 = formatdoc! {"
+        Template: Shift + O
+        Command:  Shift + L
     "};
 
     let block = Block::default()
@@ -51,9 +71,27 @@ pub fn render_preview_footer(f: &mut Frame, chunk: Rect) -> anyhow::Result<()> {
         .style(Style::default())
         .block(block.clone().title(t3));
 
+    let t4 = text::Span::from("Save").bold().underlined();
+    let p4 = Paragraph::new(text4)
+        .style(Style::default())
+        .block(block.clone().title(t4));
+
+    let t5 = text::Span::from("Merge").bold().underlined();
+    let p5 = Paragraph::new(text5)
+        .style(Style::default())
+        .block(block.clone().title(t5));
+
+    let t6 = text::Span::from("Print & Exit").bold().underlined();
+    let p6 = Paragraph::new(text6)
+        .style(Style::default())
+        .block(block.clone().title(t6));
+
     f.render_widget(p1, chunks[0]);
     f.render_widget(p2, chunks[1]);
     f.render_widget(p3, chunks[2]);
+    f.render_widget(p4, chunks[3]);
+    f.render_widget(p5, chunks[4]);
+    f.render_widget(p6, chunks[5]);
 
     Ok(())
 }
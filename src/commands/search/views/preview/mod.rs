@@ -3,7 +3,9 @@ mod footer;
 use crate::commands::search::state::view_preview::UIStatePreviewState;
 use crate::commands::search::state::{UIState, UIStateView};
 use crate::commands::search::views::preview::footer::render_preview_footer;
-use crate::commands::search::views::util::rect_center;
+use crate::commands::search::views::util::{rect_center, render_popup};
+use crate::config::manifest::MANIFEST_FILENAME;
+use crate::util::diff::DiffLine;
 use anyhow::{bail, Result};
 use ratatui::layout::{Constraint, Layout};
 use ratatui::style::{Style, Stylize};
@@ -18,13 +20,17 @@ pub fn render_preview(app: &mut UIState, f: &mut Frame) -> Result<()> {
     match &mut app.view {
         UIStateView::Preview(ref mut p) => {
             let size = f.area();
-            let title = text::Span::from(p.title.as_str()).bold().white();
+            let title = match &p.diff {
+                Some(_) => format!("{}─ Diff: .gitignore ", p.title),
+                None => p.title.clone(),
+            };
+            let title = text::Span::from(title).bold().white();
 
             let layout = Layout::default()
                 .constraints([
                     Constraint::Length(1),
                     Constraint::Min(0),
-                    Constraint::Length(3),
+                    Constraint::Length(4),
                 ])
                 .vertical_margin(0)
                 .horizontal_margin(1)
@@ -36,8 +42,12 @@ pub fn render_preview(app: &mut UIState, f: &mut Frame) -> Result<()> {
                 .border_type(BorderType::Rounded)
                 .border_style(Style::new().light_yellow());
 
-            // The preview template content
-            let mut content = Paragraph::new(p.content.as_str())
+            // The preview template content, or its diff against the .gitignore file
+            let text = match &p.diff {
+                Some(diff) => diff_text(diff),
+                None => Text::from(p.content.as_str()),
+            };
+            let mut content = Paragraph::new(text)
                 .block(block.clone().title(title.clone()))
                 .wrap(Wrap { trim: true })
                 .scroll((p.scroll_pos, 0));
@@ -68,7 +78,9 @@ pub fn render_preview(app: &mut UIState, f: &mut Frame) -> Result<()> {
             // Render the success popup if user copied content or command
             match &p.state {
                 UIStatePreviewState::Default => {}
-                UIStatePreviewState::CopiedContent | UIStatePreviewState::CopiedCommand => {
+                UIStatePreviewState::CopiedContent
+                | UIStatePreviewState::CopiedCommand
+                | UIStatePreviewState::SavedManifest => {
                     let popup_block = Block::default()
                         .title("─ Success ─")
                         .borders(Borders::ALL)
@@ -84,6 +96,17 @@ pub fn render_preview(app: &mut UIState, f: &mut Frame) -> Result<()> {
                             Line::from(text::Span::from("Template copied to clipboard")),
                             Line::from(text::Span::from(popup_note).italic().dark_gray()),
                         ])
+                    } else if let UIStatePreviewState::SavedManifest = &p.state {
+                        Text::from(vec![
+                            Line::from(text::Span::from(format!(
+                                "Templates written to {MANIFEST_FILENAME}"
+                            ))),
+                            Line::from(
+                                text::Span::from("Generate the ignore file with: gitnr sync")
+                                    .italic()
+                                    .dark_gray(),
+                            ),
+                        ])
                     } else {
                         Text::from(vec![
                             Line::from(text::Span::from("CLI command copied to clipboard")),
@@ -104,7 +127,9 @@ pub fn render_preview(app: &mut UIState, f: &mut Frame) -> Result<()> {
                         .wrap(Wrap { trim: false })
                         .block(popup_block);
 
-                    let area = if let UIStatePreviewState::CopiedContent = &p.state {
+                    let area = if let UIStatePreviewState::CopiedContent
+                    | UIStatePreviewState::SavedManifest = &p.state
+                    {
                         rect_center(72, popup_text_height - 1, None, size)
                     } else {
                         rect_center(72, popup_text_height, None, size)
@@ -113,6 +138,76 @@ pub fn render_preview(app: &mut UIState, f: &mut Frame) -> Result<()> {
                     f.render_widget(Clear, area); // this clears out the background
                     f.render_widget(popup_content, area);
                 }
+                UIStatePreviewState::Saved(path) => {
+                    let popup_block = Block::default()
+                        .title("─ Success ─")
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::new().light_green())
+                        .padding(Padding::new(2, 1, 0, 0));
+
+                    let path = path.to_str().unwrap_or("...unknown path");
+                    let popup_text = Text::from(vec![
+                        Line::from(text::Span::from("Template written to path")),
+                        Line::from(text::Span::from(path).italic().dark_gray()),
+                    ]);
+                    render_popup(f, popup_block, popup_text);
+                }
+                UIStatePreviewState::SaveFailed(error) => {
+                    let popup_block = Block::default()
+                        .title("─ Error ─")
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::new().light_red())
+                        .padding(Padding::new(2, 1, 0, 0));
+
+                    let popup_text = Text::from(
+                        error
+                            .lines()
+                            .map(|line| Line::from(text::Span::from(line)))
+                            .collect::<Vec<_>>(),
+                    );
+                    render_popup(f, popup_block, popup_text);
+                }
+                UIStatePreviewState::PromptPath(input) => {
+                    let popup_block = Block::default()
+                        .title("─ Save As ─")
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::new().light_yellow())
+                        .padding(Padding::new(2, 1, 0, 0));
+
+                    let popup_text = Text::from(vec![
+                        Line::from(text::Span::from(input.value()).bold()),
+                        Line::from(
+                            text::Span::from("Enter: Save  Esc: Cancel")
+                                .italic()
+                                .dark_gray(),
+                        ),
+                    ]);
+                    let area = render_popup(f, popup_block, popup_text);
+
+                    // Place the cursor in the input, offset by the border and padding
+                    let cursor = input.visual_cursor() as u16;
+                    f.set_cursor_position((area.x + 3 + cursor, area.y + 1));
+                }
+                UIStatePreviewState::ConfirmOverwrite(path) => {
+                    let popup_block = Block::default()
+                        .title("─ Overwrite ─")
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::new().light_yellow())
+                        .padding(Padding::new(2, 1, 0, 0));
+
+                    let path = path.to_str().unwrap_or("...unknown path");
+                    let popup_text = Text::from(vec![
+                        Line::from(text::Span::from("File already exists, overwrite it?")),
+                        Line::from(text::Span::from(path).italic().dark_gray()),
+                        Line::from(""),
+                        Line::from(text::Span::from("y: Yes  n: No").bold()),
+                    ]);
+                    render_popup(f, popup_block, popup_text);
+                }
             }
         }
         _ => {
@@ -121,3 +216,24 @@ pub fn render_preview(app: &mut UIState, f: &mut Frame) -> Result<()> {
     }
     Ok(())
 }
+
+/// Returns the diff lines styled like a colored unified diff
+fn diff_text(diff: &[DiffLine]) -> Text<'_> {
+    if diff.is_empty() {
+        let message = "No changes, the .gitignore file is the same as the preview";
+        return Text::from(text::Span::from(message).italic().dark_gray());
+    }
+
+    let lines = diff.iter().map(|line| {
+        let span = text::Span::from(line.text());
+        Line::from(match line {
+            DiffLine::OldHeader(_) => span.bold().light_red(),
+            DiffLine::NewHeader(_) => span.bold().light_green(),
+            DiffLine::Hunk(_) => span.cyan(),
+            DiffLine::Delete(_) => span.light_red(),
+            DiffLine::Insert(_) => span.light_green(),
+            DiffLine::Equal(_) => span.dark_gray(),
+        })
+    });
+    Text::from(lines.collect::<Vec<_>>())
+}
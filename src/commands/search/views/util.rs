@@ -1,4 +1,10 @@
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::text::Text;
+use ratatui::widgets::{Block, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+
+/// Frames of the loading indicator animation
+const SPINNER: [&str; 8] = ["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"];
 
 /// Helper function for creating a UI block title that extends the border lines
 pub fn title_string(title: String) -> String {
@@ -6,6 +12,11 @@ pub fn title_string(title: String) -> String {
     format!("{} {} {}", "", title, "")
 }
 
+/// Returns the frame of the loading indicator animation for the number of ticks
+pub fn spinner(ticks: usize) -> &'static str {
+    SPINNER[ticks % SPINNER.len()]
+}
+
 /// Helper function to create a centered rect using up certain percentage of the available rect `r`
 /// Taken from: https://github.com/ratatui-org/ratatui/blob/dc552116cf5e83c7ffcc2f5299c00d2315490c1d/examples/popup.rs#L96
 pub fn rect_center_pct(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -63,3 +74,20 @@ pub fn rect_center(width: u16, height: u16, y_offset: Option<i16>, r: Rect) -> R
         )
         .split(popup_layout[1])[1]
 }
+
+/// Renders a popup in the center of the screen sized to its text, returning its area
+pub fn render_popup(f: &mut Frame, block: Block, text: Text) -> Rect {
+    // Count the wrapped lines, the text is 67 columns wide inside the border and padding
+    let lines: usize = text
+        .lines
+        .iter()
+        .map(|l| l.width().max(1).div_ceil(67))
+        .sum();
+    let height = ((lines + 2) as u16).min(f.area().height);
+    let area = rect_center(72, height, None, f.area());
+    let content = Paragraph::new(text).wrap(Wrap { trim: false }).block(block);
+
+    f.render_widget(Clear, area); // this clears out the background
+    f.render_widget(content, area);
+    area
+}
@@ -0,0 +1,29 @@
+use crate::cli::CommandSync;
+use crate::commands::create::{success_msg, write_output};
+use crate::config::lockfile::Lockfile;
+use crate::config::manifest::Manifest;
+use anyhow::Result;
+
+pub fn command(cmd: &CommandSync) -> Result<()> {
+    let path = Manifest::resolve_path(cmd.manifest.as_deref())?;
+    let manifest = Manifest::load(&path)?;
+    let output = if cmd.locked {
+        let lockfile = Lockfile::load(&Lockfile::path_near(&path))?;
+        manifest.content_locked(&lockfile)?
+    } else {
+        manifest.content()?
+    };
+
+    // Print output to stdout
+    if cmd.stdout {
+        println!("{output}");
+        return Ok(());
+    }
+
+    // Write output to the file declared in the manifest
+    let out_path = manifest.output_path(&path);
+    write_output(&out_path, &output, manifest.merge)?;
+    success_msg(out_path);
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use crate::cli::CommandUpdate;
+use crate::config::lockfile::{LockedTemplate, Lockfile, LockfileChange, LOCKFILE_FILENAME};
+use crate::config::manifest::Manifest;
+use crate::template::list::TemplateList;
+use anyhow::{bail, Context, Result};
+use indoc::{formatdoc, printdoc};
+use std::env;
+use yansi::Paint;
+
+pub fn command(cmd: &CommandUpdate) -> Result<()> {
+    // Lock the provided templates, otherwise the templates declared in the manifest
+    let is_manifest = cmd.templates.is_empty();
+    let (templates, path) = if is_manifest {
+        let manifest_path = Manifest::resolve_path(cmd.manifest.as_deref())?;
+        let manifest = Manifest::load(&manifest_path)?;
+        (manifest.templates()?, Lockfile::path_near(&manifest_path))
+    } else {
+        let templates = TemplateList::parse(&cmd.templates)
+            .with_context(|| "Failed to parse provided template arguments".to_string())?;
+        let cwd = env::current_dir().with_context(|| "Failed to get current directory")?;
+        (templates, cwd.join(LOCKFILE_FILENAME))
+    };
+
+    if templates.is_empty() {
+        bail!(formatdoc! {"
+            No templates to lock
+
+            Provide templates to the update command or declare them in the project manifest:
+             gitnr update [TEMPLATE]..."})
+    }
+
+    // The manifest declares all the templates, while provided templates only update their own
+    // entries and keep the other ones
+    let current = Lockfile::load_or_default(&path)?;
+    let resolved = Lockfile::resolve(&templates)?;
+    let updated = match is_manifest {
+        true => resolved,
+        false => current.merge(&resolved),
+    };
+    let changes = current.changes(&updated);
+
+    if changes.is_empty() {
+        printdoc! {"\n{title} {message}\n\n",
+            title=" Success ".on_green().dim().white().bold(),
+            message="Lockfile is already up to date",
+        }
+        return Ok(());
+    }
+
+    updated.save(&path)?;
+
+    println!();
+    for change in changes.iter() {
+        match change {
+            LockfileChange::Added(new) => {
+                println!(
+                    "  {} {} {}",
+                    "+".green().bold(),
+                    new.spec,
+                    revision(new).dim()
+                )
+            }
+            LockfileChange::Removed(old) => {
+                println!(
+                    "  {} {} {}",
+                    "-".red().bold(),
+                    old.spec,
+                    revision(old).dim()
+                )
+            }
+            LockfileChange::Updated(old, new) => println!(
+                "  {} {} {} -> {}",
+                "~".yellow().bold(),
+                new.spec,
+                revision(old).dim(),
+                revision(new)
+            ),
+        }
+    }
+
+    printdoc! {"\n{title} {path}\n\n",
+        title=" Success ".on_green().dim().white().bold(),
+        path=format!("Lockfile written to path: {}", path.to_str().unwrap_or("...unknown path")),
+    }
+
+    Ok(())
+}
+
+/// Short description of the locked template revision
+fn revision(locked: &LockedTemplate) -> String {
+    let hash = locked.hash.split(':').next_back().unwrap_or(&locked.hash);
+    match &locked.commit {
+        Some(commit) => format!("(commit {}, {})", short(commit), short(hash)),
+        None => format!("({})", short(hash)),
+    }
+}
+
+/// Shortens a SHA or hash for display
+fn short(value: &str) -> &str {
+    value.get(..7).unwrap_or(value)
+}
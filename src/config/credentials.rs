@@ -0,0 +1,49 @@
+use crate::config::settings::{ForgeSettings, Settings, Token};
+use crate::template::collection::bitbucket::BITBUCKET_API;
+use crate::template::collection::gitea::GITEA_URL;
+use crate::template::collection::gitlab::GITLAB_URL;
+use std::env;
+use url::Url;
+
+/// Environment variables the GitHub token is read from, in order of precedence
+const GITHUB_TOKEN_ENV: [&str; 2] = ["GITHUB_TOKEN", "GH_TOKEN"];
+
+/// Hosts serving the GitHub API and raw repository files
+const GITHUB_HOSTS: [&str; 3] = ["github.com", "api.github.com", "raw.githubusercontent.com"];
+
+/// Returns the access token to send with a request to the URL, if any.
+///
+/// Tokens are looked up from the per-host credentials in the config file, then for GitHub
+/// hosts from the GITHUB_TOKEN or GH_TOKEN variables, and finally from the provider settings.
+pub fn token_for(url: &str) -> Option<Token> {
+    let host = Url::parse(url).ok()?.host_str()?.to_lowercase();
+    let settings = Settings::get().ok()?;
+
+    if let Some(token) = settings.credentials.get(&host) {
+        return Some(token.clone());
+    }
+
+    if GITHUB_HOSTS.contains(&host.as_str()) {
+        return GITHUB_TOKEN_ENV
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|token| !token.trim().is_empty())
+            .map(|token| Token::new(&token))
+            .or_else(|| settings.github.token.clone());
+    }
+
+    [
+        (&settings.gitlab, GITLAB_URL),
+        (&settings.gitea, GITEA_URL),
+        (&settings.bitbucket, BITBUCKET_API),
+    ]
+    .into_iter()
+    .find(|(forge, default)| forge_host(forge, default).is_some_and(|h| h == host))
+    .and_then(|(forge, _)| forge.token.clone())
+}
+
+/// Returns the host name of the forge base URL
+fn forge_host(forge: &ForgeSettings, default: &str) -> Option<String> {
+    let url = Url::parse(&forge.base_url(default)).ok()?;
+    url.host_str().map(|host| host.to_lowercase())
+}
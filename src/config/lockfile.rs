@@ -0,0 +1,262 @@
+use crate::template::collection::github::{gh_commit_sha, GITHUB_RAW};
+use crate::template::item::cache::TemplateCache;
+use crate::template::item::{fetch_url, Template};
+use crate::template::list::TemplateList;
+use crate::util::parallel::parallel_map;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const LOCKFILE_FILENAME: &str = "gitnr.lock";
+const LOCKFILE_VERSION: u32 = 1;
+
+/// Pins the templates used to generate an ignore file to exact upstream revisions
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Lockfile {
+    /// Version of the lockfile format
+    pub version: u32,
+    /// The locked templates
+    #[serde(default, rename = "template")]
+    pub templates: Vec<LockedTemplate>,
+}
+
+/// A template pinned to the exact content it resolved to
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LockedTemplate {
+    /// The prefixed template argument
+    pub spec: String,
+    /// The URL (or file path) the content is fetched from
+    pub url: String,
+    /// The upstream git commit SHA if the template comes from a GitHub repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// The hash of the template content
+    pub hash: String,
+}
+
+/// A change between two versions of a lockfile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockfileChange {
+    Added(LockedTemplate),
+    Removed(LockedTemplate),
+    Updated(LockedTemplate, LockedTemplate),
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self {
+            version: LOCKFILE_VERSION,
+            templates: Vec::new(),
+        }
+    }
+}
+
+impl Lockfile {
+    /// Returns the lockfile path stored alongside the given file, e.g. the project manifest
+    pub fn path_near(path: &Path) -> PathBuf {
+        match path.parent() {
+            Some(dir) => dir.join(LOCKFILE_FILENAME),
+            None => PathBuf::from(LOCKFILE_FILENAME),
+        }
+    }
+
+    /// Reads and parses the lockfile at the given path
+    pub fn load(path: &Path) -> Result<Self> {
+        let path_str = path.to_str().unwrap_or("...unknown path");
+        if !path.is_file() {
+            bail!("No lockfile found at path, create one with 'gitnr update'\n{path_str}")
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read lockfile at path\n{path_str}"))?;
+        let lockfile: Self = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse lockfile at path\n{path_str}"))?;
+        if lockfile.version != LOCKFILE_VERSION {
+            bail!(
+                "Unsupported lockfile version {}, expected version {LOCKFILE_VERSION}\n{path_str}",
+                lockfile.version
+            )
+        }
+        Ok(lockfile)
+    }
+
+    /// Reads the lockfile at the given path if it exists, otherwise returns an empty lockfile
+    pub fn load_or_default(path: &Path) -> Result<Self> {
+        if path.exists() {
+            Self::load(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Writes the lockfile to the given path
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let path_str = path.to_str().unwrap_or("...unknown path");
+        let content = toml::to_string_pretty(self)
+            .with_context(|| "Failed to serialize lockfile to TOML".to_string())?;
+        let content =
+            format!("# This file is generated by gitnr, do not edit it by hand\n\n{content}");
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write lockfile at path\n{path_str}"))?;
+        Ok(())
+    }
+
+    /// Resolves the current upstream revision and content of each template in the list
+    pub fn resolve(templates: &TemplateList) -> Result<Self> {
+        let mut commits = HashMap::new();
+        let templates = templates
+            .iter()
+            .map(|t| LockedTemplate::resolve(t, &mut commits))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            version: LOCKFILE_VERSION,
+            templates,
+        })
+    }
+
+    /// Returns a copy of this lockfile with the entries of the other one, replacing the
+    /// entries for the same template arguments and adding the others at the end
+    pub fn merge(&self, other: &Lockfile) -> Self {
+        let mut merged = self.clone();
+        for new in other.templates.iter() {
+            match merged.templates.iter_mut().find(|t| t.spec == new.spec) {
+                Some(old) => *old = new.clone(),
+                None => merged.templates.push(new.clone()),
+            }
+        }
+        merged
+    }
+
+    /// Returns the locked entry for the given template argument
+    pub fn get(&self, spec: &str) -> Option<&LockedTemplate> {
+        self.templates.iter().find(|t| t.spec == spec)
+    }
+
+    /// Get the content of the templates using the exact revisions pinned in the lockfile
+    pub fn content(&self, templates: &TemplateList) -> Result<String> {
+        let specs = templates.specs()?;
+        let bodies = TemplateCache::batch(|| {
+            parallel_map(&specs, |spec| match self.get(spec) {
+                Some(locked) => locked.fetch(),
+                None => bail!(
+                    "Template '{spec}' is not in the lockfile, add it by running 'gitnr update'"
+                ),
+            })
+        })?
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+        templates.content_with(bodies)
+    }
+
+    /// Lists the changes from this lockfile to the other one
+    pub fn changes(&self, other: &Lockfile) -> Vec<LockfileChange> {
+        let mut changes = Vec::new();
+        for new in other.templates.iter() {
+            match self.get(&new.spec) {
+                None => changes.push(LockfileChange::Added(new.clone())),
+                Some(old) if old != new => {
+                    changes.push(LockfileChange::Updated(old.clone(), new.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        for old in self.templates.iter() {
+            if other.get(&old.spec).is_none() {
+                changes.push(LockfileChange::Removed(old.clone()));
+            }
+        }
+        changes
+    }
+}
+
+impl LockedTemplate {
+    /// Resolves the template to its current upstream revision and content hash
+    ///
+    /// Commit SHAs already resolved for a repository reference are reused from `commits`.
+    fn resolve(template: &Template, commits: &mut HashMap<String, String>) -> Result<Self> {
+        let spec = template.spec()?;
+        let url = template.value.url()?;
+
+        // Files from GitHub repositories are pinned to the commit the reference points to
+        if let Some((owner, repo, reference, path)) = github_raw_parts(&url) {
+            let key = format!("{owner}/{repo}/{reference}");
+            let commit = match commits.get(&key) {
+                Some(commit) => commit.clone(),
+                None => {
+                    let commit = gh_commit_sha(owner, repo, reference)?;
+                    commits.insert(key, commit.clone());
+                    commit
+                }
+            };
+            let url = format!("{GITHUB_RAW}/{owner}/{repo}/{commit}/{path}");
+            let content = fetch_url(&url, true)?;
+            return Ok(Self {
+                spec,
+                url,
+                commit: Some(commit),
+                hash: content_hash(&content),
+            });
+        }
+
+        // Other templates can only be pinned by their content
+        let content = if template.value.provider()?.is_local() {
+            template.content_body()?
+        } else {
+            fetch_url(&url, false)?
+        };
+        Ok(Self {
+            spec,
+            url,
+            commit: None,
+            hash: content_hash(&content),
+        })
+    }
+
+    /// Fetches the pinned template content and verifies it matches the locked hash
+    fn fetch(&self) -> Result<String> {
+        let template = Template::new(&self.spec)?;
+        let is_local = template.value.provider()?.is_local();
+        let mut content = if is_local {
+            template.content_body()?
+        } else {
+            fetch_url(&self.url, true)?
+        };
+        // Content that isn't pinned to a commit might be outdated in the cache, so refetch it
+        if self.commit.is_none() && !is_local && content_hash(&content) != self.hash {
+            content = fetch_url(&self.url, false)?;
+        }
+        let hash = content_hash(&content);
+        if hash != self.hash {
+            bail!(
+                "Content of template '{}' does not match the lockfile\n\n\
+                URL:      {}\n\
+                Expected: {}\n\
+                Actual:   {hash}\n\n\
+                Run 'gitnr update' to accept the new content",
+                self.spec,
+                self.url,
+                self.hash,
+            )
+        }
+        Ok(content)
+    }
+}
+
+/// Returns the hash of the template content
+pub fn content_hash(content: &str) -> String {
+    format!("sha256:{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Splits a raw GitHub file URL into its owner, repository, reference and file path
+fn github_raw_parts(url: &str) -> Option<(&str, &str, &str, &str)> {
+    let path = url.strip_prefix(GITHUB_RAW)?.strip_prefix('/')?;
+    let mut parts = path.splitn(4, '/');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(owner), Some(repo), Some(reference), Some(path)) => {
+            Some((owner, repo, reference, path))
+        }
+        _ => None,
+    }
+}
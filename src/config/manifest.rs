@@ -0,0 +1,137 @@
+use crate::config::lockfile::Lockfile;
+use crate::template::list::TemplateList;
+use crate::util::string::banner;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILENAME: &str = ".gitnr.toml";
+const DEFAULT_OUTPUT: &str = ".gitignore";
+
+/// Per repository manifest declaring the templates used to generate an ignore file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// Template arguments using the same syntax as the create command
+    #[serde(default)]
+    pub templates: Vec<String>,
+    /// Output file path, relative to the manifest directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Merge the output into a managed block instead of overwriting the file
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub merge: bool,
+    /// Extra ignore rules appended after the templates
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<String>,
+}
+
+impl Manifest {
+    /// Returns the manifest path in the current directory
+    pub fn default_path() -> Result<PathBuf> {
+        let cwd = env::current_dir().with_context(|| "Failed to get current directory")?;
+        Ok(cwd.join(MANIFEST_FILENAME))
+    }
+
+    /// Returns the given manifest path or the default one if none is provided
+    pub fn resolve_path(path: Option<&str>) -> Result<PathBuf> {
+        match path {
+            Some(path) => Ok(PathBuf::from(path)),
+            None => Self::default_path(),
+        }
+    }
+
+    /// Reads and parses the manifest at the given path
+    pub fn load(path: &Path) -> Result<Self> {
+        let path_str = path.to_str().unwrap_or("...unknown path");
+        if !path.is_file() {
+            bail!("No manifest file found at path\n{path_str}")
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read manifest file at path\n{path_str}"))?;
+        let manifest = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse manifest file at path\n{path_str}"))?;
+        Ok(manifest)
+    }
+
+    /// Reads the manifest at the given path if it exists, otherwise returns an empty manifest
+    pub fn load_or_default(path: &Path) -> Result<Self> {
+        if path.exists() {
+            Self::load(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Writes the manifest to the given path
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let path_str = path.to_str().unwrap_or("...unknown path");
+        let content = toml::to_string_pretty(self)
+            .with_context(|| "Failed to serialize manifest to TOML".to_string())?;
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write manifest file at path\n{path_str}"))?;
+        Ok(())
+    }
+
+    /// Writes the templates into the manifest at the given path, keeping its other settings
+    pub fn save_templates(path: &Path, templates: &TemplateList) -> Result<()> {
+        let mut manifest = Self::load_or_default(path)?;
+        manifest.templates = templates.specs()?;
+        manifest.save(path)
+    }
+
+    /// Returns the template list declared in the manifest
+    pub fn templates(&self) -> Result<TemplateList> {
+        TemplateList::parse(&self.templates)
+            .with_context(|| "Failed to parse manifest template arguments".to_string())
+    }
+
+    /// Returns the output file path, resolved relative to the manifest directory
+    pub fn output_path(&self, manifest_path: &Path) -> PathBuf {
+        let output = self.output.as_deref().unwrap_or(DEFAULT_OUTPUT);
+        match manifest_path.parent() {
+            Some(dir) => dir.join(output),
+            None => PathBuf::from(output),
+        }
+    }
+
+    /// Appends the manifest's extra rules to the generated template content
+    pub fn with_rules(&self, content: String) -> String {
+        if self.rules.is_empty() {
+            return content;
+        }
+        let rules = self.rules.join("\n");
+        let section = format!("{}\n{}", banner("Inline Rules"), rules.trim());
+        if content.is_empty() {
+            section
+        } else {
+            format!("{content}\n\n{section}")
+        }
+    }
+
+    /// Generates the full output content from the manifest
+    pub fn content(&self) -> Result<String> {
+        self.content_from(|templates| templates.content())
+    }
+
+    /// Generates the full output content from the manifest using the revisions in the lockfile
+    pub fn content_locked(&self, lockfile: &Lockfile) -> Result<String> {
+        self.content_from(|templates| lockfile.content(templates))
+    }
+
+    /// Generates the full output content using the given function to render the templates
+    fn content_from(&self, render: impl Fn(&TemplateList) -> Result<String>) -> Result<String> {
+        let templates = self.templates()?;
+        if templates.is_empty() && self.rules.is_empty() {
+            bail!("The manifest does not declare any templates or rules")
+        }
+        let content = if templates.is_empty() {
+            String::new()
+        } else {
+            render(&templates)?
+        };
+        Ok(self.with_rules(content))
+    }
+}
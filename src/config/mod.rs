@@ -0,0 +1,11 @@
+/// Per repository template manifest
+pub mod manifest;
+
+/// Lockfile pinning templates to exact upstream revisions
+pub mod lockfile;
+
+/// User settings from the gitnr config file
+pub mod settings;
+
+/// Access tokens for private template sources
+pub mod credentials;
@@ -0,0 +1,261 @@
+use crate::template::detect::DetectRule;
+use crate::util::package;
+use crate::util::time::Ttl;
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const SETTINGS_FILENAME: &str = "config.toml";
+const SETTINGS_ENV: &str = "GITNR_CONFIG";
+const CACHE_TTL_ENV: &str = "GITNR_CACHE_TTL";
+
+/// How long cached content stays fresh when no TTL is configured
+const DEFAULT_CACHE_TTL: Ttl = Ttl::After(Duration::from_secs(60 * 60));
+
+/// Static instance of the user settings to prevent reading the file more than once
+static SETTINGS: Lazy<Result<Settings>> = Lazy::new(Settings::load);
+
+/// User settings read from the gitnr config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// Additional project type detection rules
+    pub detect: Vec<DetectRule>,
+    /// GitHub API and raw download settings
+    pub github: GitHubSettings,
+    /// GitLab instance and repositories listed as collections
+    pub gitlab: ForgeSettings,
+    /// Gitea or Forgejo instance and repositories listed as collections
+    pub gitea: ForgeSettings,
+    /// Bitbucket API and repositories listed as collections
+    pub bitbucket: ForgeSettings,
+    /// Local directories whose .gitignore files are listed as collections
+    pub local: Vec<LocalCollection>,
+    /// Access tokens keyed by host name, taking precedence over any other token for the host
+    pub credentials: HashMap<String, Token>,
+    /// How long the fetched collections and templates are cached for
+    pub cache: CacheSettings,
+}
+
+/// The kind of cached content, which can each have a different TTL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheKind {
+    /// The template lists of the collections
+    Collections,
+    /// The template content
+    Templates,
+}
+
+/// Cache TTL settings, each being a duration such as "12h" or "7d", or "never"
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheSettings {
+    /// Default TTL of all cached content
+    pub ttl: Option<Ttl>,
+    /// TTL of the collection template lists
+    pub collections: Option<Ttl>,
+    /// TTL of the template content
+    pub templates: Option<Ttl>,
+    /// TTL overrides keyed by provider prefix, such as "gh" or "tt"
+    pub provider: HashMap<String, ProviderCacheSettings>,
+    /// TTL set with the GITNR_CACHE_TTL variable, taking precedence over all the others
+    #[serde(skip)]
+    pub env: Option<Ttl>,
+}
+
+/// Cache TTL settings for a single provider
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProviderCacheSettings {
+    /// Default TTL of all the content cached for the provider
+    pub ttl: Option<Ttl>,
+    /// TTL of the provider collection template lists
+    pub collections: Option<Ttl>,
+    /// TTL of the provider template content
+    pub templates: Option<Ttl>,
+}
+
+impl ProviderCacheSettings {
+    /// Returns the TTL configured for the kind of cached content
+    fn get(&self, kind: CacheKind) -> Option<Ttl> {
+        let ttl = match kind {
+            CacheKind::Collections => self.collections,
+            CacheKind::Templates => self.templates,
+        };
+        ttl.or(self.ttl)
+    }
+}
+
+impl CacheSettings {
+    /// Returns the TTL of the kind of content cached for the provider with the given prefix.
+    ///
+    /// The most specific setting is used, starting with the environment variable, then the
+    /// provider settings and then the global ones, defaulting to 1 hour.
+    pub fn ttl(&self, prefix: &str, kind: CacheKind) -> Ttl {
+        let prefix = prefix.trim_end_matches(':');
+        let provider = self
+            .provider
+            .iter()
+            .find(|(key, _)| key.trim_end_matches(':') == prefix)
+            .and_then(|(_, settings)| settings.get(kind));
+        let global = match kind {
+            CacheKind::Collections => self.collections,
+            CacheKind::Templates => self.templates,
+        };
+        self.env
+            .or(provider)
+            .or(global)
+            .or(self.ttl)
+            .unwrap_or(DEFAULT_CACHE_TTL)
+    }
+}
+
+/// Settings for the GitHub template providers
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GitHubSettings {
+    /// Access token used when the GITHUB_TOKEN or GH_TOKEN variables are not set
+    pub token: Option<Token>,
+}
+
+/// Settings for a git forge template provider
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ForgeSettings {
+    /// Base URL of the forge, used instead of the provider default
+    pub url: Option<String>,
+    /// Access token sent to the forge host
+    pub token: Option<Token>,
+    /// Repositories, as `owner/repo/ref` with an optional directory, whose .gitignore files
+    /// are listed as collections in search
+    pub collections: Vec<String>,
+}
+
+/// A local directory of .gitignore templates
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LocalCollection {
+    /// Name of the collection, used in the template prefix `local:<name>/`
+    pub name: String,
+    /// Path to the directory, relative paths are resolved from the config file directory
+    pub path: String,
+}
+
+impl LocalCollection {
+    /// Returns the directory path, expanding a leading `~` to the home directory
+    pub fn dir(&self) -> PathBuf {
+        let path = match self.path.strip_prefix("~") {
+            Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+                match dirs::home_dir() {
+                    Some(home) => home.join(rest.trim_start_matches(['/', '\\'])),
+                    None => PathBuf::from(&self.path),
+                }
+            }
+            _ => PathBuf::from(&self.path),
+        };
+        match Settings::path().as_deref().and_then(Path::parent) {
+            Some(config_dir) if path.is_relative() => config_dir.join(path),
+            _ => path,
+        }
+    }
+}
+
+/// An access token, which is never shown when debug printed
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Token(String);
+
+impl Token {
+    pub fn new(token: &str) -> Self {
+        Self(token.trim().to_string())
+    }
+
+    /// The secret token value, only to be used in request headers
+    pub fn secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Token(***)")
+    }
+}
+
+impl ForgeSettings {
+    /// Returns the configured base URL without a trailing slash, or the default one
+    pub fn base_url(&self, default: &str) -> String {
+        self.url
+            .as_deref()
+            .unwrap_or(default)
+            .trim_end_matches('/')
+            .to_string()
+    }
+}
+
+impl Settings {
+    /// Returns the config file path, which can be overridden with the GITNR_CONFIG variable
+    pub fn path() -> Option<PathBuf> {
+        match std::env::var_os(SETTINGS_ENV) {
+            Some(path) => Some(PathBuf::from(path)),
+            None => dirs::config_dir().map(|dir| {
+                dir.join(package::NAME.to_lowercase())
+                    .join(SETTINGS_FILENAME)
+            }),
+        }
+    }
+
+    /// Reads the settings from the config file, or uses the defaults if it doesn't exist
+    fn load() -> Result<Self> {
+        let mut settings = match Self::path() {
+            Some(path) if path.is_file() => {
+                let path_str = path.to_str().unwrap_or("...unknown path");
+                let content = fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read config file at path\n{path_str}"))?;
+                Self::parse(&content, path_str)?
+            }
+            _ => Self::default(),
+        };
+        if let Ok(ttl) = std::env::var(CACHE_TTL_ENV) {
+            let ttl = Ttl::parse(&ttl)
+                .with_context(|| format!("Invalid {CACHE_TTL_ENV} environment variable"))?;
+            settings.cache.env = Some(ttl);
+        }
+        Ok(settings)
+    }
+
+    /// Parses the settings from the config file content.
+    ///
+    /// Errors are reported with the line number instead of the source snippet and with any
+    /// string values hidden, as they could contain an access token.
+    pub fn parse(content: &str, path_str: &str) -> Result<Self> {
+        toml::from_str(content).map_err(|e| {
+            let line = e
+                .span()
+                .map(|span| content[..span.start].matches('\n').count() + 1)
+                .map(|line| format!(" on line {line}"))
+                .unwrap_or_default();
+            let message = e
+                .message()
+                .split('"')
+                .enumerate()
+                .map(|(i, part)| if i % 2 == 1 { "***" } else { part })
+                .collect::<Vec<_>>()
+                .join("\"");
+            anyhow!("Failed to parse config file at path\n{path_str}\n\n{message}{line}")
+        })
+    }
+
+    /// Get the user settings
+    pub fn get() -> Result<&'static Settings> {
+        match &*SETTINGS {
+            Ok(settings) => Ok(settings),
+            Err(e) => Err(anyhow!(format!("{:?}", e))),
+        }
+    }
+}
@@ -2,14 +2,24 @@
 
 mod cli;
 mod commands;
+mod config;
 mod template;
 mod tests;
 mod util;
 
 use crate::cli::{get_cli, Commands};
+use crate::commands::cache;
+use crate::commands::check;
 use crate::commands::completions;
 use crate::commands::create;
+use crate::commands::detect;
+use crate::commands::diff;
+use crate::commands::explain;
 use crate::commands::search;
+use crate::commands::sync;
+use crate::commands::update;
+use crate::util::fs::set_offline;
+use crate::util::notes::take_notes;
 use anyhow::Result;
 use indoc::eprintdoc;
 use yansi::Paint;
@@ -17,10 +27,21 @@ use yansi::Paint;
 fn main() -> Result<()> {
     human_panic::setup_panic!();
 
+    if get_cli().offline {
+        set_offline(true);
+    }
+
     // Handle CLI command
     let result = match &get_cli().command {
         Some(Commands::Create(cmd)) => create::command(cmd),
+        Some(Commands::Sync(cmd)) => sync::command(cmd),
+        Some(Commands::Update(cmd)) => update::command(cmd),
+        Some(Commands::Check(cmd)) => check::command(cmd),
+        Some(Commands::Detect(cmd)) => detect::command(cmd),
+        Some(Commands::Diff(cmd)) => diff::command(cmd),
+        Some(Commands::Explain(cmd)) => explain::command(cmd),
         Some(Commands::Search) => search::command(),
+        Some(Commands::Cache(cmd)) => cache::command(cmd),
         Some(Commands::Completions { shell }) => completions::command(shell),
         None => {
             use clap::CommandFactory;
@@ -29,6 +50,13 @@ fn main() -> Result<()> {
         }
     };
 
+    // Print notes to stderr so they are not mixed with any template output
+    for note in take_notes() {
+        eprintdoc! {"\n{title} {note}\n",
+            title=" Note ".on_yellow().dim().white().bold(),
+        }
+    }
+
     // Handle error output and program termination
     match result {
         Ok(_) => Ok(()),
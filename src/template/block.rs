@@ -0,0 +1,143 @@
+use crate::util::string::banner;
+use anyhow::{bail, Result};
+use indoc::formatdoc;
+use std::ops::Range;
+
+const BLOCK_BEGIN: &str = "gitnr: begin managed block";
+const BLOCK_END: &str = "gitnr: end managed block";
+
+/// The region of an ignore file whose content is generated and managed by gitnr.
+///
+/// The block is delimited by begin/end banners so that any content above or below it
+/// can be kept as-is when the templates are generated again.
+#[derive(Debug)]
+pub struct ManagedBlock {}
+
+impl ManagedBlock {
+    /// Returns the banner marking the start of the managed block
+    pub fn begin() -> String {
+        banner(BLOCK_BEGIN)
+    }
+
+    /// Returns the banner marking the end of the managed block
+    pub fn end() -> String {
+        banner(BLOCK_END)
+    }
+
+    /// Checks if a banner title is one of the managed block banners
+    pub fn is_banner_title(title: &str) -> bool {
+        title == BLOCK_BEGIN || title == BLOCK_END
+    }
+
+    /// Wraps the given content with the managed block banners
+    pub fn wrap(content: &str) -> String {
+        formatdoc! {"
+            {begin}
+            {content}
+
+            {end}",
+            begin = Self::begin(),
+            content = content.trim(),
+            end = Self::end().trim_end(),
+        }
+    }
+
+    /// Finds the byte range of the managed block in the given content (banners included)
+    pub fn find(existing: &str) -> Result<Option<Range<usize>>> {
+        // Collect the byte offset and content (without line endings) of each line
+        let mut offset = 0;
+        let lines = existing
+            .split_inclusive('\n')
+            .map(|line| {
+                let start = offset;
+                offset += line.len();
+                (start, line.trim_end_matches(['\n', '\r']))
+            })
+            .collect::<Vec<_>>();
+
+        let title = |name: &str| format!("###  {name}  ###");
+        let is_separator = |i: usize| {
+            lines
+                .get(i)
+                .is_some_and(|(_, l)| l.starts_with("###-") && l.ends_with("-###"))
+        };
+        let position = |name: &str| -> Result<Option<usize>> {
+            let title = title(name);
+            let found = lines
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, l))| l.trim() == title)
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>();
+            if found.len() > 1 {
+                bail!(
+                    "Found more than one '{name}' marker, only a single managed block is supported"
+                )
+            }
+            Ok(found.first().copied())
+        };
+
+        let (begin, end) = match (position(BLOCK_BEGIN)?, position(BLOCK_END)?) {
+            (None, None) => return Ok(None),
+            (Some(begin), Some(end)) if begin < end => (begin, end),
+            (Some(_), Some(_)) => {
+                bail!("The managed block end marker appears before its begin marker")
+            }
+            (Some(_), None) => bail!("Found the managed block begin marker without an end marker"),
+            (None, Some(_)) => bail!("Found the managed block end marker without a begin marker"),
+        };
+
+        // Include the banner separator lines surrounding the marker titles
+        let first = if begin > 0 && is_separator(begin - 1) {
+            begin - 1
+        } else {
+            begin
+        };
+        let last = if is_separator(end + 1) { end + 1 } else { end };
+
+        let start = lines[first].0;
+        let stop = lines
+            .get(last + 1)
+            .map_or(existing.len(), |(offset, _)| *offset);
+        Ok(Some(start..stop))
+    }
+
+    /// Extracts the content inside the managed block, without the banners
+    pub fn extract(existing: &str) -> Result<Option<String>> {
+        let range = match Self::find(existing)? {
+            Some(range) => range,
+            None => return Ok(None),
+        };
+        let block = existing[range].replace("\r\n", "\n");
+        let content = block
+            .trim()
+            .strip_prefix(Self::begin().trim())
+            .and_then(|c| c.strip_suffix(Self::end().trim()))
+            .unwrap_or(&block);
+        Ok(Some(content.trim().to_string()))
+    }
+
+    /// Merges the content into the existing file content.
+    ///
+    /// If a managed block already exists it is replaced and everything around it is kept
+    /// byte-for-byte, otherwise the managed block is appended to the end of the file.
+    pub fn merge(existing: &str, content: &str) -> Result<String> {
+        let block = Self::wrap(content);
+        match Self::find(existing)? {
+            Some(range) => Ok(format!(
+                "{}{block}\n{}",
+                &existing[..range.start],
+                &existing[range.end..]
+            )),
+            None if existing.trim().is_empty() => Ok(format!("{block}\n")),
+            None => {
+                let separator = if existing.ends_with('\n') {
+                    "\n"
+                } else {
+                    "\n\n"
+                };
+                Ok(format!("{existing}{separator}{block}\n"))
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use crate::template::collection::github::GITHUB_RAW;
+
+// The github/gitignore templates generated by the build script when the `bundled` feature is
+// enabled, as pairs of the path in the repository and the template content
+#[cfg(feature = "bundled")]
+include!(concat!(env!("OUT_DIR"), "/bundled.rs"));
+
+#[cfg(not(feature = "bundled"))]
+const BUNDLED_TEMPLATES: &[(&str, &str)] = &[];
+
+/// Note printed when the bundled templates are used
+pub const BUNDLED_NOTE: &str =
+    "Using the GitHub templates bundled with gitnr, which may be outdated";
+
+/// Returns the paths of the bundled github/gitignore templates, empty if none were bundled
+pub fn bundled_paths() -> Vec<&'static str> {
+    BUNDLED_TEMPLATES.iter().map(|(path, _)| *path).collect()
+}
+
+/// Returns the bundled content of the github/gitignore template at the URL
+pub fn bundled_content(url: &str) -> Option<&'static str> {
+    find_bundled(BUNDLED_TEMPLATES, url)
+}
+
+/// Returns the content of the github/gitignore template at the URL from the given pairs of
+/// template path and content
+pub fn find_bundled<'a>(templates: &[(&str, &'a str)], url: &str) -> Option<&'a str> {
+    let path = url.strip_prefix(&format!("{GITHUB_RAW}/github/gitignore/main/"))?;
+    templates
+        .iter()
+        .find(|(p, _)| *p == path)
+        .map(|(_, content)| content.trim())
+}
@@ -0,0 +1,85 @@
+use crate::template::collection::forge::RepoPath;
+use crate::util::http::http_get;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use url::Url;
+
+pub const CACHE_PATH: &str = "collections/bitbucket.json";
+pub const BITBUCKET_API: &str = "https://api.bitbucket.org/2.0";
+
+/// Number of directory levels listed below the repository root
+const MAX_DEPTH: u32 = 10;
+
+/// Number of items requested per page, the maximum allowed by the Bitbucket API
+const PAGE_LEN: u32 = 100;
+
+/// Bitbucket source listing API response
+///
+/// https://developer.atlassian.com/cloud/bitbucket/rest/api-group-source/#api-repositories-workspace-repo-slug-src-commit-path-get
+#[derive(Debug, Deserialize)]
+pub struct SourcePage {
+    pub values: Vec<SourceItem>,
+    pub next: Option<String>,
+}
+
+/// Bitbucket source item
+#[derive(Debug, Deserialize)]
+pub struct SourceItem {
+    pub path: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// Returns the Bitbucket API URL of a file or directory in the repository
+fn bb_src_url(base: &str, repo: &RepoPath) -> Result<Url> {
+    let mut url = Url::parse(&format!("{base}/repositories"))
+        .with_context(|| format!("Invalid Bitbucket URL: {base}"))?;
+    url.path_segments_mut()
+        .map_err(|_| anyhow!("Invalid Bitbucket URL: {base}"))?
+        .push(&repo.owner)
+        .push(&repo.repo)
+        .push("src")
+        .push(&repo.reference)
+        // The repository root has no path segments to join
+        .extend(repo.path.split('/').filter(|s| !s.is_empty()));
+    Ok(url)
+}
+
+/// Returns the Bitbucket API URL of the raw file content
+pub fn bb_raw_url(base: &str, repo: &RepoPath) -> Result<String> {
+    Ok(bb_src_url(base, repo)?.to_string())
+}
+
+/// Get the paths of all the files in a Bitbucket repository directory
+pub fn bb_tree(base: &str, repo: &RepoPath) -> Result<Vec<String>> {
+    let mut paths: Vec<String> = Vec::new();
+
+    let mut url = bb_src_url(base, repo)?;
+    url.query_pairs_mut()
+        .append_pair("max_depth", &MAX_DEPTH.to_string())
+        .append_pair("pagelen", &PAGE_LEN.to_string());
+    let mut next = Some(url.to_string());
+
+    // The listing is paginated, follow the next page URL until there are no more pages
+    while let Some(url) = next {
+        let res = http_get(&url)
+            .call()
+            .with_context(|| format!("Bitbucket API error when fetching repo tree\n\n{url}"))?
+            .into_string()
+            .with_context(|| {
+                format!("Failed to parse Bitbucket API response to string\n\n{url}")
+            })?;
+        let page: SourcePage = serde_json::from_str(&res)
+            .with_context(|| format!("Failed to parse Bitbucket API response to JSON\n\n{url}"))?;
+
+        paths.extend(
+            page.values
+                .into_iter()
+                .filter(|i| i.kind == "commit_file")
+                .map(|i| i.path),
+        );
+        next = page.next;
+    }
+
+    Ok(paths)
+}
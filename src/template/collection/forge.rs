@@ -0,0 +1,149 @@
+use crate::config::settings::CacheKind;
+use crate::template::item::Template;
+use crate::template::provider::TemplateProvider;
+use crate::util::fs::{
+    cache_exists, cache_filepath, cache_is_fresh, cache_is_invalidated, fetch_or_stale,
+    read_json_file, update_json_file,
+};
+use anyhow::{bail, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Templates already listed in this run, keyed by provider prefix and collection, to prevent
+/// unnecessary fetching or cache reads
+static LISTED: Lazy<Mutex<HashMap<String, Vec<Template>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// A file or directory in a repository hosted on a git forge, written as `owner/repo/ref/path`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoPath {
+    pub owner: String,
+    pub repo: String,
+    /// Branch, tag or commit
+    pub reference: String,
+    /// Path within the repository, empty for the repository root
+    pub path: String,
+}
+
+impl RepoPath {
+    /// Parses a repository path, the path after the reference is required for files
+    pub fn parse(value: &str, is_file: bool) -> Result<Self> {
+        let parts = value.trim_matches('/').splitn(4, '/').collect::<Vec<_>>();
+        let path = parts.get(3).copied().unwrap_or_default();
+        if parts.len() < 3 || parts.iter().any(|p| p.is_empty()) || (is_file && path.is_empty()) {
+            let expected = if is_file {
+                "owner/repo/ref/path"
+            } else {
+                "owner/repo/ref[/directory]"
+            };
+            bail!("[Ignore Template] Invalid repository path: {value}\nExpected: {expected}")
+        }
+        Ok(Self {
+            owner: parts[0].to_string(),
+            repo: parts[1].to_string(),
+            reference: parts[2].to_string(),
+            path: path.trim_end_matches('/').to_string(),
+        })
+    }
+}
+
+/// The cached .gitignore templates of the repositories listed by a forge provider
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct ForgeCache {
+    collections: HashMap<String, ForgeCollection>,
+}
+
+/// The .gitignore templates found in a repository
+#[derive(Debug, Deserialize, Serialize)]
+struct ForgeCollection {
+    /// When the templates were last updated
+    updated: SystemTime,
+    /// The forge base URL the templates were listed from
+    url: String,
+    templates: Vec<Template>,
+}
+
+/// Lists the .gitignore templates in a repository collection, initializing from the cache
+/// or using the `list` function to fetch the repository file paths if necessary
+pub fn forge_templates(
+    provider: &dyn TemplateProvider,
+    cache_path: &str,
+    base_url: &str,
+    collection: &str,
+    list: impl FnOnce(&RepoPath) -> Result<Vec<String>>,
+) -> Result<Vec<Template>> {
+    let key = format!("{}{collection}", provider.prefix());
+    if let Some(templates) = LISTED.lock().unwrap().get(&key) {
+        return Ok(templates.clone());
+    }
+
+    let cache_path = cache_filepath(cache_path);
+    let cache_invalidated = cache_is_invalidated(&key);
+
+    // Caches that can't be read are treated as missing
+    let cache: ForgeCache = if cache_exists(&cache_path) {
+        read_json_file(&cache_path).unwrap_or_default()
+    } else {
+        ForgeCache::default()
+    };
+
+    // Refresh the collection if it's invalidated, past its TTL or was listed from another URL
+    let cached = cache
+        .collections
+        .get(collection)
+        .filter(|c| c.url == base_url);
+    let fresh = match cached {
+        Some(c)
+            if !cache_invalidated
+                && cache_is_fresh(c.updated, provider.prefix(), CacheKind::Collections)? =>
+        {
+            Some(c)
+        }
+        _ => None,
+    };
+    let templates = match fresh {
+        Some(fresh) => fresh.templates.clone(),
+        None => {
+            let stale = cached.map(|c| c.templates.clone());
+            fetch_or_stale("repository template list", stale, || {
+                let repo = RepoPath::parse(collection, false)?;
+                let dir = if repo.path.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}/", repo.path)
+                };
+                let templates = list(&repo)?
+                    .into_iter()
+                    .filter(|path| path.starts_with(&dir) && path.ends_with(".gitignore"))
+                    .map(|path| {
+                        Template::new(&format!(
+                            "{}{}/{}/{}/{path}",
+                            provider.prefix(),
+                            repo.owner,
+                            repo.repo,
+                            repo.reference
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let listed = ForgeCollection {
+                    updated: SystemTime::now(),
+                    url: base_url.to_string(),
+                    templates: templates.clone(),
+                };
+                // Merge into the saved cache under its lock to keep concurrent listings
+                update_json_file(&cache_path, |saved: Option<ForgeCache>| {
+                    let mut saved = saved.unwrap_or_default();
+                    saved.collections.insert(collection.to_string(), listed);
+                    saved
+                })?;
+                Ok(templates)
+            })?
+        }
+    };
+
+    LISTED.lock().unwrap().insert(key, templates.clone());
+    Ok(templates)
+}
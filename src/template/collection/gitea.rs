@@ -0,0 +1,89 @@
+use crate::template::collection::forge::RepoPath;
+use crate::util::http::http_get;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use url::Url;
+
+pub const CACHE_PATH: &str = "collections/gitea.json";
+pub const GITEA_URL: &str = "https://gitea.com";
+
+/// Number of tree items requested per page
+const PER_PAGE: u32 = 1000;
+
+/// Gitea tree API response, also used by Forgejo
+///
+/// https://docs.gitea.com/api/#tag/repository/operation/GetTree
+#[derive(Debug, Deserialize)]
+pub struct Tree {
+    pub tree: Vec<TreeItem>,
+    pub total_count: usize,
+}
+
+/// Gitea tree item
+#[derive(Debug, Deserialize)]
+pub struct TreeItem {
+    pub path: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// Returns the Gitea API URL for a repository endpoint
+fn gitea_repo_url(base: &str, repo: &RepoPath, endpoint: &[&str]) -> Result<Url> {
+    let mut url = Url::parse(&format!("{base}/api/v1/repos"))
+        .with_context(|| format!("Invalid Gitea URL: {base}"))?;
+    url.path_segments_mut()
+        .map_err(|_| anyhow!("Invalid Gitea URL: {base}"))?
+        .push(&repo.owner)
+        .push(&repo.repo)
+        .extend(endpoint);
+    Ok(url)
+}
+
+/// Returns the Gitea API URL of the raw file content
+pub fn gitea_raw_url(base: &str, repo: &RepoPath) -> Result<String> {
+    let mut endpoint = vec!["raw"];
+    endpoint.extend(repo.path.split('/'));
+    let mut url = gitea_repo_url(base, repo, &endpoint)?;
+    url.query_pairs_mut().append_pair("ref", &repo.reference);
+    Ok(url.to_string())
+}
+
+/// Get the paths of all the files in a Gitea repository
+pub fn gitea_tree(base: &str, repo: &RepoPath) -> Result<Vec<String>> {
+    let mut paths: Vec<String> = Vec::new();
+    let mut page = 1;
+    let mut listed = 0;
+
+    // The tree is paginated, request pages until all the items have been listed
+    loop {
+        let mut url = gitea_repo_url(base, repo, &["git", "trees", &repo.reference])?;
+        url.query_pairs_mut()
+            .append_pair("recursive", "true")
+            .append_pair("per_page", &PER_PAGE.to_string())
+            .append_pair("page", &page.to_string());
+        let url = url.to_string();
+
+        let res = http_get(&url)
+            .call()
+            .with_context(|| format!("Gitea API error when fetching repo tree\n\n{url}"))?
+            .into_string()
+            .with_context(|| format!("Failed to parse Gitea API response to string\n\n{url}"))?;
+        let tree: Tree = serde_json::from_str(&res)
+            .with_context(|| format!("Failed to parse Gitea API response to JSON\n\n{url}"))?;
+
+        listed += tree.tree.len();
+        let done = tree.tree.is_empty() || listed >= tree.total_count;
+        paths.extend(
+            tree.tree
+                .into_iter()
+                .filter(|i| i.kind == "blob")
+                .map(|i| i.path),
+        );
+        if done {
+            break;
+        }
+        page += 1;
+    }
+
+    Ok(paths)
+}
@@ -1,26 +1,40 @@
+use crate::config::settings::CacheKind;
+use crate::template::bundled::{bundled_paths, BUNDLED_NOTE};
 use crate::template::item::Template;
+use crate::template::provider::github::{
+    GitHubCommunityProvider, GitHubGlobalProvider, GitHubProvider,
+};
+use crate::template::provider::TemplateProvider;
 use crate::util::fs::{
-    cache_exists, cache_filepath, cache_is_invalidated, read_json_file, write_json_file,
+    cache_exists, cache_filepath, cache_is_fresh, cache_is_invalidated, fetch_or_stale, is_offline,
+    read_json_file, write_json_file,
 };
-use crate::util::http::http;
-use anyhow::{anyhow, Context, Result};
+use crate::util::http::{call_revalidate, http_get, is_network_error, Revalidated, Validators};
+use crate::util::notes::note;
+use anyhow::{bail, Context, Result};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
 use std::time::SystemTime;
 
-const CACHE_PATH: &str = "collections/github.json";
+pub const CACHE_PATH: &str = "collections/github.json";
 pub const GITHUB_RAW: &str = "https://raw.githubusercontent.com";
 pub const GITHUB_API_ENDPOINT: &str = "https://api.github.com";
 pub const GITHUB_API_ACCEPT: &str = "application/vnd.github+json";
+pub const GITHUB_API_ACCEPT_SHA: &str = "application/vnd.github.sha";
 
-/// Static instance of GitHub templates to prevent unnecessary fetching or cache reads
-static TEMPLATES: Lazy<Result<GithubTemplates>> = Lazy::new(GithubTemplates::new);
+/// Static instance of GitHub templates to prevent unnecessary fetching or cache reads. Only set
+/// once loaded, so loading them again after a failure fetches them again.
+static TEMPLATES: Lazy<Mutex<Option<GithubTemplates>>> = Lazy::new(|| Mutex::new(None));
 
 /// The different GitHub ignore template collections
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct GithubTemplates {
     /// When the templates were last updated
     updated: SystemTime,
+    /// Validators of the repo tree response the templates were listed from
+    #[serde(default)]
+    validators: Validators,
     /// Root directory templates
     root: Vec<Template>,
     /// Global directory templates
@@ -33,65 +47,110 @@ impl GithubTemplates {
     /// Create GitHub templates list by Initializing from cache or fetching if necessary
     fn new() -> Result<Self> {
         let cache_path = cache_filepath(CACHE_PATH);
-        let cache_exists = cache_exists(&cache_path);
         let cache_invalidated = cache_is_invalidated(&cache_path);
-        let check_cache = cache_exists && !cache_invalidated;
-
-        // If the cache exists and is not invalidated, read it, otherwise create a new instance
-        let mut _self = if check_cache {
-            read_json_file(&cache_path)?
-        } else {
-            Self {
-                updated: SystemTime::UNIX_EPOCH,
-                root: Vec::new(),
-                global: Vec::new(),
-                community: Vec::new(),
+
+        // Caches written in an older format can't be read and are treated as missing
+        let cached: Option<Self> = cache_exists(&cache_path)
+            .then(|| read_json_file(&cache_path).ok())
+            .flatten();
+
+        match cached {
+            // Use the cache if it's not invalidated and within its TTL
+            Some(cached)
+                if !cache_invalidated
+                    && cache_is_fresh(
+                        cached.updated,
+                        GitHubProvider.prefix(),
+                        CacheKind::Collections,
+                    )? =>
+            {
+                Ok(cached)
+            }
+            // Otherwise refresh the cache, falling back to the outdated cache or the bundled
+            // templates when offline or the network is unreachable
+            stale => {
+                let fetched = fetch_or_stale("GitHub template list", stale.clone(), || {
+                    let fetched = Self::fetch(stale.as_ref())?;
+                    write_json_file(&cache_path, &fetched)?;
+                    Ok(fetched)
+                });
+                fetched.or_else(|e| match Self::bundled()? {
+                    Some(bundled) if is_offline() || is_network_error(&e) => {
+                        note(BUNDLED_NOTE);
+                        Ok(bundled)
+                    }
+                    _ => Err(e),
+                })
+            }
+        }
+    }
+
+    /// Fetch the templates from the GitHub API, reusing the cached templates if the repo tree
+    /// hasn't changed since they were listed
+    fn fetch(cached: Option<&Self>) -> Result<Self> {
+        let validators = cached.map(|c| &c.validators);
+        let tree = gh_tree("github", "gitignore", "main", true, validators)?;
+        let (tree, validators) = match (tree, cached) {
+            (Revalidated::NotModified, Some(cached)) => {
+                return Ok(Self {
+                    updated: SystemTime::now(),
+                    ..cached.clone()
+                })
             }
+            (Revalidated::NotModified, None) => {
+                bail!("Unexpected 304 response from the GitHub API when fetching repo tree")
+            }
+            (Revalidated::Modified(tree, validators), _) => (tree, validators),
         };
 
-        // Refresh cache if it's older than 1 hour and save it to the cache file
-        if _self.updated.elapsed()?.as_secs() > 60 * 60 {
-            _self = Self::fetch()?;
-            write_json_file(&cache_path, &_self)?;
-        }
+        // Filter anything in the repo tree that's not a blob (file)
+        let paths = tree
+            .tree
+            .iter()
+            .filter(|i| i.kind != TreeItemKind::Tree)
+            .map(|i| i.path.as_str());
+        Ok(Self {
+            validators,
+            ..Self::from_paths(paths)?
+        })
+    }
 
-        Ok(_self)
+    /// Create the templates from the templates bundled at compile time, if any
+    fn bundled() -> Result<Option<Self>> {
+        let paths = bundled_paths();
+        if paths.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self::from_paths(paths.into_iter())?))
     }
 
-    /// Fetch the templates from the GitHub API
-    fn fetch() -> Result<Self> {
+    /// Create the templates from the file paths in the github/gitignore repository
+    fn from_paths<'a>(paths: impl Iterator<Item = &'a str>) -> Result<Self> {
         let mut root: Vec<Template> = Vec::new();
         let mut global: Vec<Template> = Vec::new();
         let mut community: Vec<Template> = Vec::new();
 
-        // Get the repo tree and filter anything that's not a blob (file)
-        let tree = gh_tree("github", "gitignore", "main", true)?;
-        let tree = tree
-            .tree
-            .iter()
-            .filter(|i| i.kind != TreeItemKind::Tree)
-            .collect::<Vec<_>>();
-
-        for item in tree.iter() {
+        for path in paths {
             // Skip anything that's not a .gitignore file
-            let path = match item.path.strip_suffix(".gitignore") {
+            let path = match path.strip_suffix(".gitignore") {
                 Some(path) => path,
                 _ => continue,
             };
 
             // Root template
             if !path.contains('/') {
-                let template = Template::new(&format!("gh:{path}"))?;
+                let template = Template::new(&format!("{}{path}", GitHubProvider.prefix()))?;
                 root.push(template);
             }
             // Global template
             else if let Some(path) = path.strip_prefix("Global/") {
-                let template = Template::new(&format!("ghg:{path}"))?;
+                let template = Template::new(&format!("{}{path}", GitHubGlobalProvider.prefix()))?;
                 global.push(template);
             }
             // Community template
             else if let Some(path) = path.strip_prefix("community/") {
-                let template = Template::new(&format!("ghc:{path}"))?;
+                let template =
+                    Template::new(&format!("{}{path}", GitHubCommunityProvider.prefix()))?;
                 community.push(template);
             }
         }
@@ -100,34 +159,35 @@ impl GithubTemplates {
 
         Ok(Self {
             updated,
+            validators: Validators::default(),
             root,
             global,
             community,
         })
     }
 
+    /// Runs the function with the GitHub templates, loading them if they aren't yet
+    fn with<T>(f: impl FnOnce(&Self) -> T) -> Result<T> {
+        let mut templates = TEMPLATES.lock().unwrap();
+        match &*templates {
+            Some(templates) => Ok(f(templates)),
+            None => Ok(f(templates.insert(Self::new()?))),
+        }
+    }
+
     /// Get the root GitHub ignore templates
     pub fn root() -> Result<Vec<Template>> {
-        match &*TEMPLATES {
-            Ok(templates) => Ok(templates.root.clone()),
-            Err(e) => Err(anyhow!(format!("{:?}", e))),
-        }
+        Self::with(|templates| templates.root.clone())
     }
 
     /// Get the global GitHub ignore templates
     pub fn global() -> Result<Vec<Template>> {
-        match &*TEMPLATES {
-            Ok(templates) => Ok(templates.global.clone()),
-            Err(e) => Err(anyhow!(format!("{:?}", e))),
-        }
+        Self::with(|templates| templates.global.clone())
     }
 
     /// Get the community GitHub ignore templates
     pub fn community() -> Result<Vec<Template>> {
-        match &*TEMPLATES {
-            Ok(templates) => Ok(templates.community.clone()),
-            Err(e) => Err(anyhow!(format!("{:?}", e))),
-        }
+        Self::with(|templates| templates.community.clone())
     }
 }
 
@@ -162,28 +222,53 @@ pub struct TreeItem {
     pub url: String,
 }
 
-/// Get the GitHub tree for a repository
-pub fn gh_tree(owner: &str, repo: &str, branch: &str, recursive: bool) -> Result<Tree> {
-    let mut url = format!(
-        "{GITHUB_API_ENDPOINT}/repos/{owner}/{repo}/git/trees/{branch}"
-    );
+/// Get the GitHub tree for a repository, unless it's unchanged since the `cached` response.
+///
+/// Conditional requests answered with a 304 don't count against the API rate limit.
+pub fn gh_tree(
+    owner: &str,
+    repo: &str,
+    branch: &str,
+    recursive: bool,
+    cached: Option<&Validators>,
+) -> Result<Revalidated<Tree>> {
+    let mut url = format!("{GITHUB_API_ENDPOINT}/repos/{owner}/{repo}/git/trees/{branch}");
 
     if recursive {
         url = format!("{url}?recursive=true")
     }
 
-    let res = http()
-        .get(&url)
-        .set("Accept", GITHUB_API_ACCEPT)
-        .call()
+    let request = http_get(&url).set("Accept", GITHUB_API_ACCEPT);
+    let (res, validators) = match call_revalidate(request, cached)
         .with_context(|| format!("GitHub API error when fetching repo tree\n\n{url}"))?
+    {
+        Revalidated::NotModified => return Ok(Revalidated::NotModified),
+        Revalidated::Modified(res, validators) => (res, validators),
+    };
+    let res = res
         .into_string()
         .with_context(|| format!("Failed to parse GitHub API response to string\n\n{url}"))?;
 
     let parsed = serde_json::from_str(&res)
         .with_context(|| format!("Failed to parse GitHub API response to JSON\n\n{url}"))?;
 
-    Ok(parsed)
+    Ok(Revalidated::Modified(parsed, validators))
+}
+
+/// Get the SHA of the commit a git reference (branch, tag or commit) of a repository points to
+///
+/// https://docs.github.com/en/rest/commits/commits?apiVersion=2022-11-28#get-a-commit
+pub fn gh_commit_sha(owner: &str, repo: &str, reference: &str) -> Result<String> {
+    let url = format!("{GITHUB_API_ENDPOINT}/repos/{owner}/{repo}/commits/{reference}");
+
+    let res = http_get(&url)
+        .set("Accept", GITHUB_API_ACCEPT_SHA)
+        .call()
+        .with_context(|| format!("GitHub API error when fetching commit SHA\n\n{url}"))?
+        .into_string()
+        .with_context(|| format!("Failed to parse GitHub API response to string\n\n{url}"))?;
+
+    Ok(res.trim().to_string())
 }
 
 /*
@@ -209,8 +294,7 @@ pub fn gh_branch(owner: &str, repo: &str, branch: &str) -> Result<Branch> {
         GITHUB_API_ENDPOINT
     );
 
-    let res = http()
-        .get(&url)
+    let res = http_get(&url)
         .set("Accept", GITHUB_API_ACCEPT)
         .call()
         .with_context(|| format!("GitHub API error when fetching repo branch\n\n{}", url))?
@@ -0,0 +1,76 @@
+use crate::template::collection::forge::RepoPath;
+use crate::util::http::http_get;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use url::Url;
+
+pub const CACHE_PATH: &str = "collections/gitlab.json";
+pub const GITLAB_URL: &str = "https://gitlab.com";
+
+/// Number of tree items requested per page, the maximum allowed by the GitLab API
+const PER_PAGE: u32 = 100;
+
+/// GitLab repository tree item
+///
+/// https://docs.gitlab.com/ee/api/repositories.html#list-repository-tree
+#[derive(Debug, Deserialize)]
+pub struct TreeItem {
+    pub path: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// Returns the GitLab API URL for a project endpoint
+fn gl_project_url(base: &str, repo: &RepoPath, endpoint: &[&str]) -> Result<Url> {
+    let mut url = Url::parse(&format!("{base}/api/v4/projects"))
+        .with_context(|| format!("Invalid GitLab URL: {base}"))?;
+    url.path_segments_mut()
+        .map_err(|_| anyhow!("Invalid GitLab URL: {base}"))?
+        .push(&format!("{}/{}", repo.owner, repo.repo))
+        .extend(endpoint);
+    Ok(url)
+}
+
+/// Returns the GitLab API URL of the raw file content
+pub fn gl_raw_url(base: &str, repo: &RepoPath) -> Result<String> {
+    let mut url = gl_project_url(base, repo, &["repository", "files", &repo.path, "raw"])?;
+    url.query_pairs_mut().append_pair("ref", &repo.reference);
+    Ok(url.to_string())
+}
+
+/// Get the paths of all the files in a GitLab repository
+pub fn gl_tree(base: &str, repo: &RepoPath) -> Result<Vec<String>> {
+    let mut paths: Vec<String> = Vec::new();
+    let mut page = String::from("1");
+
+    // The tree is paginated, follow the next page header until there are no more pages
+    while !page.is_empty() {
+        let mut url = gl_project_url(base, repo, &["repository", "tree"])?;
+        url.query_pairs_mut()
+            .append_pair("ref", &repo.reference)
+            .append_pair("recursive", "true")
+            .append_pair("per_page", &PER_PAGE.to_string())
+            .append_pair("page", &page);
+        let url = url.to_string();
+
+        let res = http_get(&url)
+            .call()
+            .with_context(|| format!("GitLab API error when fetching repo tree\n\n{url}"))?;
+        page = res.header("x-next-page").unwrap_or_default().to_string();
+
+        let res = res
+            .into_string()
+            .with_context(|| format!("Failed to parse GitLab API response to string\n\n{url}"))?;
+        let items: Vec<TreeItem> = serde_json::from_str(&res)
+            .with_context(|| format!("Failed to parse GitLab API response to JSON\n\n{url}"))?;
+
+        paths.extend(
+            items
+                .into_iter()
+                .filter(|i| i.kind == "blob")
+                .map(|i| i.path),
+        );
+    }
+
+    Ok(paths)
+}
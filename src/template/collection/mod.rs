@@ -1,38 +1,47 @@
+pub mod bitbucket;
+pub mod forge;
+pub mod gitea;
 pub mod github;
+pub mod gitlab;
 pub mod toptal;
 
-use crate::template::collection::github::GithubTemplates;
-use crate::template::collection::toptal::TopTalTemplates;
 use crate::template::item::Template;
+use crate::template::provider::{provider, providers, TemplateProvider};
 use anyhow::Result;
 
-/// The available predefined .gitignore template collections
+/// A predefined .gitignore template collection listed by a template provider
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum TemplateCollectionKind {
-    TopTal,
-    GitHub,
-    GitHubCommunity,
-    GitHubGlobal,
+pub struct TemplateCollectionKind {
+    /// Prefix of the provider listing the collection
+    pub provider: String,
+    /// Identifier of the collection within the provider
+    pub id: String,
+    /// Display name of the collection
+    pub name: String,
 }
 
 impl TemplateCollectionKind {
+    pub fn new(provider: &dyn TemplateProvider, id: &str, name: &str) -> Self {
+        Self {
+            provider: provider.prefix().to_string(),
+            id: id.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    /// Get all the collections listed by the registered template providers
+    pub fn all() -> Vec<Self> {
+        providers().flat_map(|p| p.collections()).collect()
+    }
+
     /// Display name of the template collection
     pub fn name(&self) -> &str {
-        match self {
-            TemplateCollectionKind::TopTal => "TopTal",
-            TemplateCollectionKind::GitHub => "GitHub",
-            TemplateCollectionKind::GitHubCommunity => "GitHub Community",
-            TemplateCollectionKind::GitHubGlobal => "GitHub Global",
-        }
+        &self.name
     }
+
     /// Get a vector of the templates in the collection
     pub fn get(&self) -> Result<Vec<Template>> {
-        Ok(match self {
-            TemplateCollectionKind::TopTal => TopTalTemplates::templates()?,
-            TemplateCollectionKind::GitHub => GithubTemplates::root()?,
-            TemplateCollectionKind::GitHubCommunity => GithubTemplates::community()?,
-            TemplateCollectionKind::GitHubGlobal => GithubTemplates::global()?,
-        })
+        provider(&self.provider)?.list(&self.id)
     }
 }
 
@@ -1,23 +1,32 @@
+use crate::config::settings::CacheKind;
 use crate::template::item::Template;
+use crate::template::provider::toptal::TopTalProvider;
+use crate::template::provider::TemplateProvider;
 use crate::util::fs::{
-    cache_exists, cache_filepath, cache_is_invalidated, read_json_file, write_json_file,
+    cache_exists, cache_filepath, cache_is_fresh, cache_is_invalidated, fetch_or_stale,
+    read_json_file, write_json_file,
 };
-use crate::util::http::http;
-use anyhow::{anyhow, Context, Result};
+use crate::util::http::{call_revalidate, http_get, Revalidated, Validators};
+use anyhow::{bail, Context, Result};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
 use std::time::SystemTime;
 
-const CACHE_PATH: &str = "collections/toptal.json";
+pub const CACHE_PATH: &str = "collections/toptal.json";
 pub const TOPTAL_API: &str = "https://www.toptal.com/developers/gitignore/api";
 
-/// Static instance of TopTal templates to prevent unnecessary fetching or cache reads
-static TEMPLATES: Lazy<Result<TopTalTemplates>> = Lazy::new(TopTalTemplates::new);
+/// Static instance of TopTal templates to prevent unnecessary fetching or cache reads. Only set
+/// once loaded, so loading them again after a failure fetches them again.
+static TEMPLATES: Lazy<Mutex<Option<TopTalTemplates>>> = Lazy::new(|| Mutex::new(None));
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TopTalTemplates {
     /// When the templates were last updated
     updated: SystemTime,
+    /// Validators of the list response the templates were created from
+    #[serde(default)]
+    validators: Validators,
     templates: Vec<Template>,
 }
 
@@ -25,55 +34,77 @@ impl TopTalTemplates {
     /// Create TopTal templates list by initializing from cache or fetching if necessary
     fn new() -> Result<Self> {
         let cache_path = cache_filepath(CACHE_PATH);
-        let cache_exists = cache_exists(&cache_path);
         let cache_invalidated = cache_is_invalidated(&cache_path);
-        let check_cache = cache_exists && !cache_invalidated;
 
-        // If the cache exists and is not invalidated, read it, otherwise create a new instance
-        let mut _self = if check_cache {
-            read_json_file(&cache_path)?
-        } else {
-            Self {
-                updated: SystemTime::UNIX_EPOCH,
-                templates: Vec::new(),
-            }
-        };
+        // Caches written in an older format can't be read and are treated as missing
+        let cached: Option<Self> = cache_exists(&cache_path)
+            .then(|| read_json_file(&cache_path).ok())
+            .flatten();
 
-        // Refresh cache if it's older than 1 hour and save it to the cache file
-        if _self.updated.elapsed()?.as_secs() > 60 * 60 {
-            _self = Self::fetch()?;
-            write_json_file(&cache_path, &_self)?;
+        match cached {
+            // Use the cache if it's not invalidated and within its TTL
+            Some(cached)
+                if !cache_invalidated
+                    && cache_is_fresh(
+                        cached.updated,
+                        TopTalProvider.prefix(),
+                        CacheKind::Collections,
+                    )? =>
+            {
+                Ok(cached)
+            }
+            // Otherwise refresh the cache, falling back to the outdated cache when offline
+            stale => fetch_or_stale("TopTal template list", stale.clone(), || {
+                let fetched = Self::fetch(stale.as_ref())?;
+                write_json_file(&cache_path, &fetched)?;
+                Ok(fetched)
+            }),
         }
-
-        Ok(_self)
     }
 
-    /// Fetch the templates from the TopTal API
-    fn fetch() -> Result<Self> {
+    /// Fetch the templates from the TopTal API, reusing the cached templates if the list
+    /// hasn't changed since they were fetched
+    fn fetch(cached: Option<&Self>) -> Result<Self> {
         let url = format!("{TOPTAL_API}/list?format=lines");
 
-        let list = http()
-            .get(&url)
-            .call()
-            .with_context(|| "Failed to fetch template list from TopTal")?
+        let res = call_revalidate(http_get(&url), cached.map(|c| &c.validators))
+            .with_context(|| "Failed to fetch template list from TopTal")?;
+        let (res, validators) = match (res, cached) {
+            (Revalidated::NotModified, Some(cached)) => {
+                return Ok(Self {
+                    updated: SystemTime::now(),
+                    ..cached.clone()
+                })
+            }
+            (Revalidated::NotModified, None) => {
+                bail!("Unexpected 304 response when fetching template list from TopTal")
+            }
+            (Revalidated::Modified(res, validators), _) => (res, validators),
+        };
+        let list = res
             .into_string()
             .with_context(|| "Failed to parse TopTal template list response to string")?;
 
         let templates = list
             .lines()
-            .map(|s| Template::new(&format!("tt:{s}")))
+            .map(|s| Template::new(&format!("{}{s}", TopTalProvider.prefix())))
             .collect::<Result<Vec<Template>>>()?;
 
         let updated = SystemTime::now();
 
-        Ok(Self { updated, templates })
+        Ok(Self {
+            updated,
+            validators,
+            templates,
+        })
     }
 
     /// Get TopTal templates list
     pub fn templates() -> Result<Vec<Template>> {
-        match &*TEMPLATES {
-            Ok(templates) => Ok(templates.templates.clone()),
-            Err(e) => Err(anyhow!(format!("{:?}", e))),
+        let mut templates = TEMPLATES.lock().unwrap();
+        match &*templates {
+            Some(templates) => Ok(templates.templates.clone()),
+            None => Ok(templates.insert(Self::new()?).templates.clone()),
         }
     }
 }
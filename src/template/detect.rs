@@ -0,0 +1,201 @@
+use crate::config::settings::Settings;
+use crate::template::item::Template;
+use crate::util::notes::note;
+use crate::util::string::wildcard_match;
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// The default maximum directory depth scanned for marker files
+pub const DETECT_DEPTH: usize = 3;
+
+/// The built-in detection rules
+const BUILTIN_RULES: &str = include_str!("detect.toml");
+
+/// Maps marker files found in a project to the templates it should use
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DetectRule {
+    /// File or directory name patterns identifying the project type. Directory patterns end
+    /// with a '/' and patterns containing a '/' are matched against the relative path.
+    pub markers: Vec<String>,
+    /// Templates suggested when one of the markers is found
+    pub templates: Vec<String>,
+}
+
+/// The list of built-in detection rules
+#[derive(Debug, Deserialize)]
+struct DetectRules {
+    detect: Vec<DetectRule>,
+}
+
+/// A template suggested for the project along with the reason why
+#[derive(Debug, Clone)]
+pub struct Detection {
+    pub template: Template,
+    pub reason: String,
+}
+
+/// A file or directory found in the project
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct ProjectEntry {
+    /// Path relative to the project root, using '/' as the separator
+    path: String,
+    is_dir: bool,
+}
+
+/// Detects the templates a project should use from the files in it
+#[derive(Debug)]
+pub struct Detector {
+    rules: Vec<DetectRule>,
+    depth: usize,
+}
+
+impl Detector {
+    /// Creates a detector using the user defined rules followed by the built-in ones
+    pub fn new(depth: usize) -> Result<Self> {
+        Self::with_settings(Settings::get()?, depth)
+    }
+
+    /// Creates a detector using the rules of the given settings followed by the built-in ones
+    pub fn with_settings(settings: &Settings, depth: usize) -> Result<Self> {
+        let builtin: DetectRules = toml::from_str(BUILTIN_RULES)
+            .with_context(|| "Failed to parse built-in detection rules".to_string())?;
+        let rules = settings
+            .detect
+            .iter()
+            .cloned()
+            .chain(builtin.detect)
+            .collect();
+        Ok(Self { rules, depth })
+    }
+
+    /// Scans the directory and returns the suggested templates in rule order
+    pub fn detect(&self, root: &Path) -> Result<Vec<Detection>> {
+        let entries = self.entries(root)?;
+        let mut detections: Vec<Detection> = Vec::new();
+
+        for rule in self.rules.iter() {
+            let found = rule
+                .markers
+                .iter()
+                .find_map(|marker| entries.iter().find(|e| marker_matches(marker, e)));
+            let entry = match found {
+                Some(entry) => entry,
+                None => continue,
+            };
+
+            for spec in rule.templates.iter() {
+                let template = match canonical_template(Template::new(spec)?) {
+                    Some(template) => template,
+                    None => {
+                        note(&format!(
+                            "Skipped detected template {spec}, which doesn't exist in its collection"
+                        ));
+                        continue;
+                    }
+                };
+                if detections.iter().any(|d| d.template == template) {
+                    continue;
+                }
+                let suffix = if entry.is_dir { "/" } else { "" };
+                detections.push(Detection {
+                    template,
+                    reason: format!("found {}{suffix}", entry.path),
+                });
+            }
+        }
+
+        Ok(detections)
+    }
+
+    /// Lists the project files and directories, skipping anything already ignored.
+    ///
+    /// Entries directly in the root are always included as editor and OS files such
+    /// as `.idea/` are usually already ignored, but still need a template.
+    fn entries(&self, root: &Path) -> Result<Vec<ProjectEntry>> {
+        let root_str = root.to_str().unwrap_or("...unknown path");
+        let mut entries: Vec<ProjectEntry> = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+
+        let mut push = |path: &Path, is_dir: bool| {
+            let path = match path.strip_prefix(root) {
+                Ok(path) if !path.as_os_str().is_empty() => path,
+                _ => return,
+            };
+            let path = path
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            if seen.insert(path.clone()) {
+                entries.push(ProjectEntry { path, is_dir });
+            }
+        };
+
+        let root_entries = fs::read_dir(root)
+            .with_context(|| format!("Failed to read directory at path\n{root_str}"))?;
+        for entry in root_entries.flatten() {
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            push(&entry.path(), is_dir);
+        }
+
+        let walker = WalkBuilder::new(root)
+            .hidden(false)
+            .max_depth(Some(self.depth))
+            .filter_entry(|e| e.file_name() != ".git")
+            .build();
+        for entry in walker.flatten() {
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            push(entry.path(), is_dir);
+        }
+
+        Ok(entries)
+    }
+}
+
+/// Checks if the marker pattern matches the project entry
+fn marker_matches(marker: &str, entry: &ProjectEntry) -> bool {
+    let (marker, dir_only) = match marker.strip_suffix('/') {
+        Some(marker) => (marker, true),
+        None => (marker, false),
+    };
+    if dir_only && !entry.is_dir {
+        return false;
+    }
+    if marker.contains('/') {
+        wildcard_match(marker, &entry.path)
+    } else {
+        let name = entry.path.rsplit('/').next().unwrap_or(&entry.path);
+        wildcard_match(marker, name)
+    }
+}
+
+/// Resolves the template to the one in its collection, matching the name case-insensitively.
+///
+/// Returns `None` if the template doesn't exist in its collection. Templates from other
+/// sources or collections that can't be loaded are returned as they are.
+fn canonical_template(template: Template) -> Option<Template> {
+    let provider = match template.value.provider() {
+        Ok(provider) => provider,
+        Err(_) => return Some(template),
+    };
+    let kinds = provider.collections();
+    if kinds.is_empty() {
+        return Some(template);
+    }
+    let mut items: Vec<Template> = Vec::new();
+    for kind in kinds.iter() {
+        match kind.get() {
+            Ok(collection) => items.extend(collection),
+            Err(_) => return Some(template),
+        }
+    }
+    let name = template.value.name().ok()?;
+    items
+        .into_iter()
+        .find(|t| t.value.name().is_ok_and(|n| n.eq_ignore_ascii_case(&name)))
+}
@@ -1,22 +1,58 @@
+use crate::config::settings::CacheKind;
+use crate::template::provider::provider_for_url;
 use crate::util::fs::{
-    cache_exists, cache_filepath, cache_is_invalidated, read_json_file, write_json_file,
+    cache_exists, cache_filepath, cache_is_fresh, cache_is_invalidated, is_parse_error,
+    quarantine_file, read_json_file, update_json_file,
 };
+use crate::util::http::Validators;
+use crate::util::notes::note;
 use anyhow::Result;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Mutex;
 use std::time::SystemTime;
 
 const CACHE_FILENAME: &str = "template-content.json";
 static CACHE: Lazy<Mutex<HashMap<String, TemplateCacheItem>>> =
-    Lazy::new(|| TemplateCache::initialize().expect("Failed to initialize template cache"));
+    Lazy::new(TemplateCache::initialize);
+
+/// Number of running batches, changes are only saved to disk when the last one ends
+static BATCHES: AtomicUsize = AtomicUsize::new(0);
+
+/// Whether there are changes that haven't been saved to disk because of a running batch
+static UNSAVED: AtomicBool = AtomicBool::new(false);
 
 /// Represents a cached .gitignore template
-#[derive(Debug, Deserialize, Serialize)]
-struct TemplateCacheItem {
-    updated: SystemTime,
-    content: String,
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TemplateCacheItem {
+    pub updated: SystemTime,
+    pub content: String,
+    /// Validators of the response the content was fetched from, used to revalidate it
+    #[serde(default)]
+    pub validators: Validators,
+}
+
+/// Details of a cached .gitignore template
+#[derive(Debug, Clone)]
+pub struct TemplateCacheEntry {
+    /// The URL the template was fetched from
+    pub key: String,
+    /// When the template was last updated
+    pub updated: SystemTime,
+    /// Size of the content in bytes
+    pub size: usize,
+}
+
+impl TemplateCacheEntry {
+    fn new(key: &str, item: &TemplateCacheItem) -> Self {
+        Self {
+            key: key.to_string(),
+            updated: item.updated,
+            size: item.content.len(),
+        }
+    }
 }
 
 /// The cache interface for .gitignore templates
@@ -28,34 +64,90 @@ impl TemplateCache {
         cache_filepath(CACHE_FILENAME)
     }
 
-    /// Initialize the template cache with an existing cache file or an empty cache
-    fn initialize() -> Result<Mutex<HashMap<String, TemplateCacheItem>>> {
+    /// Initialize the template cache with an existing cache file or an empty cache.
+    ///
+    /// A cache file that can't be parsed is quarantined instead of failing every command, while
+    /// one that can't be read is left in place.
+    fn initialize() -> Mutex<HashMap<String, TemplateCacheItem>> {
         let path = Self::get_cache_path();
         let exists = cache_exists(&path);
         let invalidated = cache_is_invalidated(&path);
 
         let map = if exists && !invalidated {
-            read_json_file(&path)?
+            read_json_file(&path).unwrap_or_else(|e| {
+                match is_parse_error(&e) {
+                    true => quarantine_file(&path),
+                    false => note(&format!("{e:#}\nContinuing without the cached templates")),
+                }
+                HashMap::new()
+            })
         } else {
             HashMap::new()
         };
 
-        Ok(Mutex::new(map))
+        Mutex::new(map)
+    }
+
+    /// Save the template cache to disk, keeping the templates cached by other processes since
+    /// it was read unless the predicate returns false for them
+    fn save_retain(
+        map: &HashMap<String, TemplateCacheItem>,
+        keep: impl Fn(&TemplateCacheEntry) -> bool,
+    ) -> Result<()> {
+        let path = cache_filepath(CACHE_FILENAME);
+        update_json_file(&path, |saved| {
+            let mut merged: HashMap<String, TemplateCacheItem> = saved.unwrap_or_default();
+            merged.retain(|key, item| keep(&TemplateCacheEntry::new(key, item)));
+            for (key, item) in map.iter() {
+                match merged.get(key) {
+                    Some(saved) if saved.updated > item.updated => {}
+                    _ => {
+                        merged.insert(key.clone(), item.clone());
+                    }
+                }
+            }
+            merged
+        })
     }
 
     /// Save the template cache to disk
     fn save(map: &HashMap<String, TemplateCacheItem>) -> Result<()> {
-        let path = cache_filepath(CACHE_FILENAME);
-        write_json_file::<HashMap<String, TemplateCacheItem>>(&path, map)?;
-        Ok(())
+        TemplateCache::save_retain(map, |_| true)
     }
 
-    /// Get a cached template
+    /// Save the template cache to disk, unless a batch is running in which case it's saved
+    /// when the batch ends. Must be called while holding the cache lock.
+    fn save_or_defer(map: &HashMap<String, TemplateCacheItem>) -> Result<()> {
+        if BATCHES.load(Ordering::SeqCst) > 0 {
+            UNSAVED.store(true, Ordering::SeqCst);
+            return Ok(());
+        }
+        TemplateCache::save(map)
+    }
+
+    /// Runs the function with the cache changes saved to disk once at the end, instead of
+    /// rewriting the whole cache file for every template set
+    pub fn batch<T>(f: impl FnOnce() -> T) -> Result<T> {
+        BATCHES.fetch_add(1, Ordering::SeqCst);
+        let result = f();
+        // The cache is only locked, which reads it from disk the first time, if changes were
+        // deferred while the batch was running
+        if BATCHES.fetch_sub(1, Ordering::SeqCst) == 1 && UNSAVED.load(Ordering::SeqCst) {
+            let map = CACHE.lock().unwrap();
+            if UNSAVED.swap(false, Ordering::SeqCst) {
+                TemplateCache::save(&map)?;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Get a cached template if it's within the TTL of the provider owning its URL
     pub fn get(key: &str) -> Result<Option<String>> {
         let map = CACHE.lock().unwrap();
         match map.get(key) {
             Some(item) => {
-                if item.updated.elapsed()?.as_secs() > 60 * 60 {
+                let prefix = provider_for_url(key).map_or("", |p| p.prefix());
+                if !cache_is_fresh(item.updated, prefix, CacheKind::Templates)? {
                     return Ok(None);
                 }
                 Ok(Some(item.content.clone()))
@@ -64,17 +156,66 @@ impl TemplateCache {
         }
     }
 
+    /// Get a cached template regardless of its age
+    pub fn get_stale(key: &str) -> Option<String> {
+        let map = CACHE.lock().unwrap();
+        map.get(key).map(|item| item.content.clone())
+    }
+
+    /// Get a cached template regardless of its age, along with the validators to check if
+    /// it's still current
+    pub fn get_stale_with_validators(key: &str) -> Option<(String, Validators)> {
+        let map = CACHE.lock().unwrap();
+        map.get(key)
+            .map(|item| (item.content.clone(), item.validators.clone()))
+    }
+
     /// Set a cached template
-    pub fn set(key: &str, content: &str) -> Result<()> {
+    pub fn set(key: &str, content: &str, validators: Validators) -> Result<()> {
         let mut map = CACHE.lock().unwrap();
         map.insert(
             key.to_string(),
             TemplateCacheItem {
                 updated: SystemTime::now(),
                 content: content.to_string(),
+                validators,
             },
         );
-        TemplateCache::save(&map)?;
+        TemplateCache::save_or_defer(&map)?;
+        Ok(())
+    }
+
+    /// Mark a cached template as current after revalidating it, resetting its age
+    pub fn touch(key: &str) -> Result<()> {
+        let mut map = CACHE.lock().unwrap();
+        if let Some(item) = map.get_mut(key) {
+            item.updated = SystemTime::now();
+            TemplateCache::save_or_defer(&map)?;
+        }
         Ok(())
     }
+
+    /// List the cached templates sorted by key
+    pub fn entries() -> Vec<TemplateCacheEntry> {
+        let map = CACHE.lock().unwrap();
+        let mut entries = map
+            .iter()
+            .map(|(key, item)| TemplateCacheEntry::new(key, item))
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        entries
+    }
+
+    /// Remove the cached templates the predicate returns false for, returning how many
+    /// were removed
+    pub fn retain(keep: impl Fn(&TemplateCacheEntry) -> bool) -> Result<usize> {
+        let mut map = CACHE.lock().unwrap();
+        let before = map.len();
+        map.retain(|key, item| keep(&TemplateCacheEntry::new(key, item)));
+        let removed = before - map.len();
+        if removed > 0 {
+            TemplateCache::save_retain(&map, &keep)?;
+        }
+        Ok(removed)
+    }
 }
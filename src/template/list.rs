@@ -1,6 +1,9 @@
+use crate::template::item::cache::TemplateCache;
 use crate::template::item::Template;
-use crate::util::string::trim_duplicate_lines;
-use anyhow::Result;
+use crate::template::pattern::dedupe;
+use crate::util::notes::note;
+use crate::util::parallel::parallel_map;
+use anyhow::{bail, Result};
 use std::ops::Deref;
 
 /// Represents a list of .gitignore templates
@@ -21,6 +24,18 @@ impl TemplateList {
         Self { items: templates }
     }
 
+    /// Parse template arguments into a template list, splitting any comma separated values
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let templates = args
+            .iter()
+            .flat_map(|arg| arg.split(','))
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(Template::new)
+            .collect::<Result<Vec<Template>>>()?;
+        Ok(Self::new(templates))
+    }
+
     /// Get the number of templates in the list
     pub fn count(&self) -> usize {
         self.len()
@@ -39,19 +54,40 @@ impl TemplateList {
             .join("\n\n"))
     }
 
-    /// Get the content of the templates in the list joined together
+    /// Get the content of the templates in the list joined together.
+    ///
+    /// The template bodies are fetched concurrently and the cache is saved once they're done.
     pub fn content(&self) -> Result<String> {
-        if self.count() == 1 {
-            return self[0].content(None);
+        let templates_content_body =
+            TemplateCache::batch(|| parallel_map(&self.items, |tmpl| tmpl.content_body()))?
+                .into_iter()
+                .collect::<Result<Vec<String>>>()?;
+
+        self.content_with(templates_content_body)
+    }
+
+    /// Get the content of the templates in the list joined together using the given bodies
+    pub fn content_with(&self, templates_content_body: Vec<String>) -> Result<String> {
+        if templates_content_body.len() != self.count() {
+            bail!("Number of template bodies does not match the number of templates")
         }
 
-        // If more than 1 template in list, trim duplicate lines
-        let templates_content_body = self
-            .iter()
-            .map(|tmpl| tmpl.content_body())
-            .collect::<Result<Vec<String>>>()?;
+        if self.count() == 1 {
+            return self[0].content(Some(templates_content_body[0].as_str()));
+        }
 
-        let templates_content_body = trim_duplicate_lines(templates_content_body);
+        // If more than 1 template in list, trim redundant patterns and report any conflicts
+        let deduped = dedupe(templates_content_body);
+        for conflict in deduped.conflicts.iter() {
+            note(&format!(
+                "'{}' in {} re-ignores paths un-ignored by '{}' in {}",
+                conflict.ignored,
+                self[conflict.ignored_in].value.title()?,
+                conflict.negated,
+                self[conflict.negated_in].value.title()?,
+            ));
+        }
+        let templates_content_body = deduped.bodies;
 
         let templates_content = self
             .iter()
@@ -62,18 +98,14 @@ impl TemplateList {
         Ok(templates_content.join("\n\n"))
     }
 
+    /// Get the prefixed template arguments that can be parsed back into this list
+    pub fn specs(&self) -> Result<Vec<String>> {
+        self.iter().map(|t| t.spec()).collect()
+    }
+
     /// Get the CLI command needed to generate the content of the templates
     pub fn command(&self) -> Result<String> {
-        let cmds = self
-            .iter()
-            .map(|t| {
-                t.value
-                    .name()
-                    .map(|name| format!("{}{}", t.value.prefix(), name))
-            })
-            .collect::<Result<Vec<String>>>()?;
-
-        let cmds = cmds.join(" ");
+        let cmds = self.specs()?.join(" ");
 
         Ok(format!("gitnr create {cmds}").trim().to_string())
     }
@@ -1,3 +1,9 @@
+pub mod block;
+pub mod bundled;
 pub mod collection;
+pub mod detect;
 pub mod item;
 pub mod list;
+pub mod pattern;
+pub mod provider;
+pub mod simulate;
@@ -0,0 +1,403 @@
+use crate::template::block::ManagedBlock;
+use crate::util::string::{banner_title, replace_multiple_empty_lines, wildcard_match};
+
+/// Characters with a special meaning in gitignore patterns
+const WILDCARDS: [char; 4] = ['*', '?', '[', '\\'];
+
+/// A parsed gitignore pattern line, normalized so equivalent patterns can be compared
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    /// The line the pattern was parsed from
+    pub line: String,
+    /// Un-ignores the matching paths (`!` prefix)
+    pub negated: bool,
+    /// Only matches directories (`/` suffix)
+    pub dir_only: bool,
+    /// Only matches relative to the ignore file directory (leading or middle `/`), otherwise
+    /// it matches at any depth
+    pub anchored: bool,
+    /// The glob without the negation, the leading `/` or `**/` and the trailing `/`
+    pub glob: String,
+}
+
+impl Pattern {
+    /// Parses a gitignore line, returning nothing for blank lines and comments.
+    ///
+    /// Like git, leading whitespace is part of the pattern and only unescaped trailing spaces
+    /// are removed.
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut value = trim_trailing_spaces(line);
+        if value.is_empty() || value.starts_with('#') {
+            return None;
+        }
+
+        let negated = value.starts_with('!');
+        if negated {
+            value = &value[1..];
+        }
+        let dir_only = value.ends_with('/');
+        let value = value.trim_end_matches('/');
+
+        // A leading "**/" matches in all directories, the same as no slash at all
+        let mut floating = false;
+        let mut value = value;
+        while let Some(rest) = value.strip_prefix("**/") {
+            floating = true;
+            value = rest;
+        }
+        let anchored = !floating && value.contains('/');
+        let glob = value.trim_start_matches('/').to_string();
+        if glob.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            line: line.to_string(),
+            negated,
+            dir_only,
+            anchored,
+            glob,
+        })
+    }
+
+    /// Checks if the pattern matches the path, relative to the ignore file directory and
+    /// separated with `/`, following the gitignore matching rules
+    pub fn matches(&self, path: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let glob = self.glob.chars().collect::<Vec<_>>();
+        let parts = components(path);
+        if self.anchored {
+            glob_match(&glob, &parts.join("/").chars().collect::<Vec<_>>())
+        } else {
+            // Patterns without an anchor match the trailing path components at any depth
+            let depth = if self.glob.contains('/') {
+                0
+            } else {
+                parts.len().saturating_sub(1)
+            };
+            (depth..parts.len())
+                .any(|i| glob_match(&glob, &parts[i..].join("/").chars().collect::<Vec<_>>()))
+        }
+    }
+
+    /// Checks if this pattern matches every path the other one matches, with the same effect
+    pub fn covers(&self, other: &Pattern) -> bool {
+        self.negated == other.negated
+            && self.glob == other.glob
+            && (!self.anchored || other.anchored)
+            && (!self.dir_only || other.dir_only)
+    }
+
+    /// Checks if this pattern could match any of the paths the other one matches.
+    ///
+    /// This errs on the side of caution and only returns false when they can't overlap.
+    pub fn overlaps(&self, other: &Pattern) -> bool {
+        let wild = |p: &Pattern| p.glob.contains(WILDCARDS);
+        match (wild(self), wild(other)) {
+            (true, true) => true,
+            (true, false) => matches_part(&self.glob, &other.glob),
+            (false, true) => matches_part(&other.glob, &self.glob),
+            (false, false) => {
+                let (a, b) = (components(&self.glob), components(&other.glob));
+                contains_run(&a, &b) || contains_run(&b, &a)
+            }
+        }
+    }
+}
+
+/// Matches the text against a gitignore glob, where `*` and `?` don't match a `/`, `**` as a
+/// whole path segment matches any number of directories and `[...]` matches a character class
+fn glob_match(glob: &[char], text: &[char]) -> bool {
+    segment_match(glob, text, true)
+}
+
+/// Matches the text against the rest of a glob, `segment_start` being whether the rest starts
+/// a path segment
+fn segment_match(glob: &[char], text: &[char], segment_start: bool) -> bool {
+    match glob {
+        [] => text.is_empty(),
+        // Trailing "/**" matches everything inside, "**/" matches zero or more directories
+        ['*', '*'] if segment_start => true,
+        ['*', '*', '/', rest @ ..] if segment_start => {
+            segment_match(rest, text, true)
+                || (0..text.len())
+                    .any(|i| text[i] == '/' && segment_match(rest, &text[i + 1..], true))
+        }
+        ['*', rest @ ..] => {
+            // Any other consecutive asterisks are the same as a single one
+            let rest = &rest[rest.iter().take_while(|&&c| c == '*').count()..];
+            for i in 0..=text.len() {
+                if segment_match(rest, &text[i..], false) {
+                    return true;
+                }
+                if i < text.len() && text[i] == '/' {
+                    break;
+                }
+            }
+            false
+        }
+        ['?', rest @ ..] => {
+            matches!(text, [c, ..] if *c != '/') && segment_match(rest, &text[1..], false)
+        }
+        ['[', class @ ..] => match (text, class_match(class, text.first().copied())) {
+            ([_, text @ ..], Some((true, rest))) => segment_match(rest, text, false),
+            (_, Some((false, _))) => false,
+            // An unclosed class is matched literally
+            ([c, text @ ..], None) => *c == '[' && segment_match(class, text, false),
+            _ => false,
+        },
+        ['\\', c, rest @ ..] | [c, rest @ ..] => {
+            matches!(text, [t, ..] if t == c) && segment_match(rest, &text[1..], *c == '/')
+        }
+    }
+}
+
+/// Matches the character against a class following its `[`, returning if it matched and the
+/// rest of the glob after the closing `]`, or nothing if the class isn't closed
+fn class_match(class: &[char], c: Option<char>) -> Option<(bool, &[char])> {
+    let (negated, mut i) = match class.first() {
+        Some('!' | '^') => (true, 1),
+        _ => (false, 0),
+    };
+    let mut matched = false;
+    let mut first = true;
+    while i < class.len() {
+        let start = match class[i] {
+            ']' if !first => {
+                let matched = c.is_some_and(|c| c != '/') && matched != negated;
+                return Some((matched, &class[i + 1..]));
+            }
+            '\\' if i + 1 < class.len() => {
+                i += 1;
+                class[i]
+            }
+            start => start,
+        };
+        first = false;
+        let end = match class.get(i + 1..i + 3) {
+            Some(['-', end]) if *end != ']' => {
+                i += 2;
+                *end
+            }
+            _ => start,
+        };
+        matched |= c.is_some_and(|c| start <= c && c <= end);
+        i += 1;
+    }
+    None
+}
+
+/// Removes trailing spaces, unless escaped with a backslash
+fn trim_trailing_spaces(line: &str) -> &str {
+    let trimmed = line.trim_end_matches(' ');
+    if trimmed.len() < line.len() && trimmed.ends_with('\\') {
+        &line[..trimmed.len() + 1]
+    } else {
+        trimmed
+    }
+}
+
+/// Splits a glob into its path components
+fn components(glob: &str) -> Vec<&str> {
+    glob.split('/').filter(|c| !c.is_empty()).collect()
+}
+
+/// Checks if the components of `inner` appear in a row in `outer`
+fn contains_run(outer: &[&str], inner: &[&str]) -> bool {
+    inner.is_empty() || outer.windows(inner.len()).any(|w| w == inner)
+}
+
+/// Checks if the wildcard glob matches any of the leading or trailing parts of the path
+fn matches_part(glob: &str, path: &str) -> bool {
+    let parts = components(path);
+    (0..parts.len()).any(|i| {
+        wildcard_match(glob, &parts[i..].join("/")) || wildcard_match(glob, &parts[..=i].join("/"))
+    })
+}
+
+/// A later template re-ignoring paths an earlier template un-ignored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    /// Index of the template with the negated pattern
+    pub negated_in: usize,
+    /// The negated pattern line
+    pub negated: String,
+    /// Index of the later template re-ignoring the paths
+    pub ignored_in: usize,
+    /// The pattern line re-ignoring the paths
+    pub ignored: String,
+}
+
+/// The template bodies without redundant patterns, and the conflicts between them
+#[derive(Debug)]
+pub struct Deduped {
+    pub bodies: Vec<String>,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Removes the patterns from the template bodies that are redundant because of an earlier
+/// pattern, keeping all the comments and empty lines.
+///
+/// A pattern is only redundant if an earlier one covers it and no pattern in between has the
+/// opposite effect on any of the same paths, so the combined result stays the same. Patterns
+/// in a later template that ignore paths an earlier template un-ignored are reported as
+/// conflicts, since the later pattern wins.
+pub fn dedupe(bodies: Vec<String>) -> Deduped {
+    let mut seen: Vec<(usize, Pattern)> = Vec::new();
+    // Conflicts along with the position in `seen` of the pattern re-ignoring the paths
+    let mut conflicts: Vec<(usize, Pattern, Conflict)> = Vec::new();
+    let mut result: Vec<String> = Vec::new();
+
+    for (index, body) in bodies.iter().enumerate() {
+        let mut lines: Vec<&str> = Vec::new();
+        for line in body.lines() {
+            let pattern = match Pattern::parse(line) {
+                Some(pattern) => pattern,
+                // Empty lines and comments are always included
+                None => {
+                    lines.push(line);
+                    continue;
+                }
+            };
+
+            // Search back for an earlier pattern covering this one, stopping at any pattern
+            // that could have reversed its effect in between
+            let redundant = seen
+                .iter()
+                .rev()
+                .map(|(_, p)| p)
+                .take_while(|p| p.negated == pattern.negated || !p.overlaps(&pattern))
+                .any(|p| p.covers(&pattern));
+            if redundant {
+                continue;
+            }
+
+            if !pattern.negated {
+                for (negated_in, negated) in seen.iter() {
+                    if *negated_in < index && negated.negated && negated.overlaps(&pattern) {
+                        let conflict = Conflict {
+                            negated_in: *negated_in,
+                            negated: negated.line.trim().to_string(),
+                            ignored_in: index,
+                            ignored: line.trim().to_string(),
+                        };
+                        conflicts.push((seen.len(), negated.clone(), conflict));
+                    }
+                }
+            }
+
+            lines.push(line);
+            seen.push((index, pattern));
+        }
+        result.push(replace_multiple_empty_lines(&lines.join("\n")));
+    }
+
+    // Paths un-ignored again by a later pattern, e.g. in the same template, aren't conflicts
+    let conflicts = conflicts
+        .into_iter()
+        .filter(|(position, negated, _)| !seen[*position..].iter().any(|(_, p)| p.covers(negated)))
+        .map(|(_, _, conflict)| conflict)
+        .collect();
+
+    Deduped {
+        bodies: result,
+        conflicts,
+    }
+}
+
+/// A pattern in an ignore file
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub pattern: Pattern,
+    /// Line number of the pattern in the ignore file, starting at 1
+    pub line_number: usize,
+    /// Title of the template section the pattern is in, from the section banner
+    pub section: Option<String>,
+}
+
+/// How the rules of an ignore file apply to a path
+#[derive(Debug)]
+pub struct Explanation<'a> {
+    /// Whether the path is ignored
+    pub ignored: bool,
+    /// The rules matching the path in order, the last one deciding if it's ignored
+    pub matches: Vec<&'a Rule>,
+    /// An ignored parent directory of the path and the rule ignoring it, in which case the
+    /// path is ignored and can't be un-ignored by a negated rule
+    pub ignored_parent: Option<(String, &'a Rule)>,
+}
+
+/// The rules of an ignore file
+#[derive(Debug)]
+pub struct Rules {
+    pub rules: Vec<Rule>,
+}
+
+impl Rules {
+    /// Parses the rules of an ignore file, keeping track of the template sections they're in
+    pub fn parse(content: &str) -> Self {
+        let mut section: Option<String> = None;
+        let mut rules: Vec<Rule> = Vec::new();
+        for (i, line) in content.lines().enumerate() {
+            if let Some(title) = banner_title(line) {
+                // The managed block banners don't start a template section
+                section = (!ManagedBlock::is_banner_title(title)).then(|| title.to_string());
+                continue;
+            }
+            if let Some(pattern) = Pattern::parse(line) {
+                rules.push(Rule {
+                    pattern,
+                    line_number: i + 1,
+                    section: section.clone(),
+                });
+            }
+        }
+        Self { rules }
+    }
+
+    /// Returns the rules matching the path in order
+    fn matching(&self, path: &str, is_dir: bool) -> Vec<&Rule> {
+        self.rules
+            .iter()
+            .filter(|r| r.pattern.matches(path, is_dir))
+            .collect()
+    }
+
+    /// Checks if the last rule matching the path ignores it, without checking whether any of
+    /// its parent directories are ignored
+    pub fn ignores(&self, path: &str, is_dir: bool) -> bool {
+        self.rules
+            .iter()
+            .rev()
+            .find(|r| r.pattern.matches(path, is_dir))
+            .is_some_and(|r| !r.pattern.negated)
+    }
+
+    /// Explains how the rules apply to the path, relative to the ignore file directory
+    pub fn explain(&self, path: &str, is_dir: bool) -> Explanation<'_> {
+        let parts = components(path);
+        let matches = self.matching(path, is_dir);
+
+        // Files in an ignored directory are ignored regardless of any other rules
+        for i in 1..parts.len() {
+            let parent = parts[..i].join("/");
+            if let Some(rule) = self.matching(&parent, true).last() {
+                if !rule.pattern.negated {
+                    return Explanation {
+                        ignored: true,
+                        matches,
+                        ignored_parent: Some((parent, rule)),
+                    };
+                }
+            }
+        }
+
+        Explanation {
+            ignored: matches.last().is_some_and(|r| !r.pattern.negated),
+            matches,
+            ignored_parent: None,
+        }
+    }
+}
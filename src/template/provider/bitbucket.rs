@@ -0,0 +1,59 @@
+use crate::config::settings::Settings;
+use crate::template::collection::bitbucket::{bb_raw_url, bb_tree, BITBUCKET_API, CACHE_PATH};
+use crate::template::collection::forge::{forge_templates, RepoPath};
+use crate::template::collection::TemplateCollectionKind;
+use crate::template::item::Template;
+use crate::template::provider::TemplateProvider;
+use anyhow::Result;
+
+/// Files from a Bitbucket Cloud repository
+#[derive(Debug)]
+pub struct BitbucketProvider;
+
+impl BitbucketProvider {
+    /// Returns the configured Bitbucket API URL
+    fn base_url(&self) -> Result<String> {
+        Ok(Settings::get()?.bitbucket.base_url(BITBUCKET_API))
+    }
+}
+
+impl TemplateProvider for BitbucketProvider {
+    fn prefix(&self) -> &str {
+        "bb:"
+    }
+
+    fn title(&self) -> &str {
+        "Bitbucket"
+    }
+
+    fn url(&self, name: &str) -> Result<String> {
+        bb_raw_url(&self.base_url()?, &RepoPath::parse(name, true)?)
+    }
+
+    fn collections(&self) -> Vec<TemplateCollectionKind> {
+        let collections = match Settings::get() {
+            Ok(settings) => settings.bitbucket.collections.as_slice(),
+            Err(_) => &[],
+        };
+        collections
+            .iter()
+            .map(|c| TemplateCollectionKind::new(self, c, &format!("{}: {c}", self.title())))
+            .collect()
+    }
+
+    fn list(&self, collection: &str) -> Result<Vec<Template>> {
+        let base = self.base_url()?;
+        forge_templates(self, CACHE_PATH, &base, collection, |repo| {
+            bb_tree(&base, repo)
+        })
+    }
+
+    fn owns_url(&self, url: &str) -> bool {
+        self.base_url()
+            .is_ok_and(|base| url.starts_with(&format!("{base}/repositories/")))
+    }
+
+    fn cache_files(&self) -> Vec<String> {
+        vec![CACHE_PATH.to_string()]
+    }
+}
@@ -0,0 +1,43 @@
+use crate::template::provider::TemplateProvider;
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// Templates from a local file path
+#[derive(Debug)]
+pub struct FileProvider;
+
+impl TemplateProvider for FileProvider {
+    fn prefix(&self) -> &str {
+        "file:"
+    }
+
+    fn title(&self) -> &str {
+        "File"
+    }
+
+    fn guess(&self, input: &str) -> Option<u8> {
+        Path::new(input).exists().then_some(30)
+    }
+
+    fn url(&self, name: &str) -> Result<String> {
+        if !Path::new(name).exists() {
+            bail!(
+                "[Ignore Template] Invalid or non-existent file path: {}",
+                name
+            )
+        }
+        Ok(name.to_string())
+    }
+
+    fn fetch(&self, name: &str) -> Result<String> {
+        let path = self.url(name)?;
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read ignore file template at path\n{path}"))?;
+        Ok(content)
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
+}
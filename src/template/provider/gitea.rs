@@ -0,0 +1,59 @@
+use crate::config::settings::Settings;
+use crate::template::collection::forge::{forge_templates, RepoPath};
+use crate::template::collection::gitea::{gitea_raw_url, gitea_tree, CACHE_PATH, GITEA_URL};
+use crate::template::collection::TemplateCollectionKind;
+use crate::template::item::Template;
+use crate::template::provider::TemplateProvider;
+use anyhow::Result;
+
+/// Files from a Gitea or Forgejo repository, on gitea.com or a self-hosted instance
+#[derive(Debug)]
+pub struct GiteaProvider;
+
+impl GiteaProvider {
+    /// Returns the configured Gitea base URL
+    fn base_url(&self) -> Result<String> {
+        Ok(Settings::get()?.gitea.base_url(GITEA_URL))
+    }
+}
+
+impl TemplateProvider for GiteaProvider {
+    fn prefix(&self) -> &str {
+        "gitea:"
+    }
+
+    fn title(&self) -> &str {
+        "Gitea"
+    }
+
+    fn url(&self, name: &str) -> Result<String> {
+        gitea_raw_url(&self.base_url()?, &RepoPath::parse(name, true)?)
+    }
+
+    fn collections(&self) -> Vec<TemplateCollectionKind> {
+        let collections = match Settings::get() {
+            Ok(settings) => settings.gitea.collections.as_slice(),
+            Err(_) => &[],
+        };
+        collections
+            .iter()
+            .map(|c| TemplateCollectionKind::new(self, c, &format!("{}: {c}", self.title())))
+            .collect()
+    }
+
+    fn list(&self, collection: &str) -> Result<Vec<Template>> {
+        let base = self.base_url()?;
+        forge_templates(self, CACHE_PATH, &base, collection, |repo| {
+            gitea_tree(&base, repo)
+        })
+    }
+
+    fn owns_url(&self, url: &str) -> bool {
+        self.base_url()
+            .is_ok_and(|base| url.starts_with(&format!("{base}/api/v1/repos/")))
+    }
+
+    fn cache_files(&self) -> Vec<String> {
+        vec![CACHE_PATH.to_string()]
+    }
+}
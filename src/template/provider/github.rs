@@ -0,0 +1,170 @@
+use crate::template::collection::github::{GithubTemplates, CACHE_PATH, GITHUB_RAW};
+use crate::template::collection::TemplateCollectionKind;
+use crate::template::item::Template;
+use crate::template::provider::TemplateProvider;
+use crate::util::string::strip_prefixes;
+use anyhow::Result;
+
+/// Templates in the root directory of the github/gitignore repository
+#[derive(Debug)]
+pub struct GitHubProvider;
+
+/// Templates in the Global directory of the github/gitignore repository
+#[derive(Debug)]
+pub struct GitHubGlobalProvider;
+
+/// Templates in the community directory of the github/gitignore repository
+#[derive(Debug)]
+pub struct GitHubCommunityProvider;
+
+/// Files from any public GitHub repository
+#[derive(Debug)]
+pub struct GitHubRepoProvider;
+
+impl TemplateProvider for GitHubProvider {
+    fn prefix(&self) -> &str {
+        "gh:"
+    }
+
+    fn title(&self) -> &str {
+        "GitHub"
+    }
+
+    fn name(&self, input: &str) -> String {
+        let name = input.strip_prefix(self.prefix()).unwrap_or(input);
+        name.strip_suffix(".gitignore").unwrap_or(name).to_string()
+    }
+
+    fn url(&self, name: &str) -> Result<String> {
+        Ok(format!(
+            "{GITHUB_RAW}/github/gitignore/main/{name}.gitignore"
+        ))
+    }
+
+    fn collections(&self) -> Vec<TemplateCollectionKind> {
+        vec![TemplateCollectionKind::new(self, "root", self.title())]
+    }
+
+    fn list(&self, _collection: &str) -> Result<Vec<Template>> {
+        GithubTemplates::root()
+    }
+
+    fn owns_url(&self, url: &str) -> bool {
+        gitignore_path(url).is_some_and(|p| !p.contains('/'))
+    }
+
+    fn cache_files(&self) -> Vec<String> {
+        vec![CACHE_PATH.to_string()]
+    }
+}
+
+impl TemplateProvider for GitHubGlobalProvider {
+    fn prefix(&self) -> &str {
+        "ghg:"
+    }
+
+    fn title(&self) -> &str {
+        "GitHub Global"
+    }
+
+    fn guess(&self, input: &str) -> Option<u8> {
+        input.to_lowercase().starts_with("global/").then_some(10)
+    }
+
+    fn name(&self, input: &str) -> String {
+        let name = strip_prefixes(input, &[self.prefix(), "global/", "Global/"]).unwrap_or(input);
+        name.strip_suffix(".gitignore").unwrap_or(name).to_string()
+    }
+
+    fn url(&self, name: &str) -> Result<String> {
+        Ok(format!(
+            "{GITHUB_RAW}/github/gitignore/main/Global/{name}.gitignore"
+        ))
+    }
+
+    fn collections(&self) -> Vec<TemplateCollectionKind> {
+        vec![TemplateCollectionKind::new(self, "global", self.title())]
+    }
+
+    fn list(&self, _collection: &str) -> Result<Vec<Template>> {
+        GithubTemplates::global()
+    }
+
+    fn owns_url(&self, url: &str) -> bool {
+        gitignore_path(url).is_some_and(|p| p.starts_with("Global/"))
+    }
+
+    fn cache_files(&self) -> Vec<String> {
+        vec![CACHE_PATH.to_string()]
+    }
+}
+
+impl TemplateProvider for GitHubCommunityProvider {
+    fn prefix(&self) -> &str {
+        "ghc:"
+    }
+
+    fn title(&self) -> &str {
+        "GitHub Community"
+    }
+
+    fn guess(&self, input: &str) -> Option<u8> {
+        input.to_lowercase().starts_with("community/").then_some(10)
+    }
+
+    fn name(&self, input: &str) -> String {
+        let name =
+            strip_prefixes(input, &[self.prefix(), "community/", "Community/"]).unwrap_or(input);
+        name.strip_suffix(".gitignore").unwrap_or(name).to_string()
+    }
+
+    fn url(&self, name: &str) -> Result<String> {
+        Ok(format!(
+            "{GITHUB_RAW}/github/gitignore/main/community/{name}.gitignore"
+        ))
+    }
+
+    fn collections(&self) -> Vec<TemplateCollectionKind> {
+        vec![TemplateCollectionKind::new(self, "community", self.title())]
+    }
+
+    fn list(&self, _collection: &str) -> Result<Vec<Template>> {
+        GithubTemplates::community()
+    }
+
+    fn owns_url(&self, url: &str) -> bool {
+        gitignore_path(url).is_some_and(|p| p.starts_with("community/"))
+    }
+
+    fn cache_files(&self) -> Vec<String> {
+        vec![CACHE_PATH.to_string()]
+    }
+}
+
+impl TemplateProvider for GitHubRepoProvider {
+    fn prefix(&self) -> &str {
+        "repo:"
+    }
+
+    fn title(&self) -> &str {
+        "Repo"
+    }
+
+    fn guess(&self, input: &str) -> Option<u8> {
+        (input.matches('/').count() >= 3).then_some(20)
+    }
+
+    fn url(&self, name: &str) -> Result<String> {
+        Ok(format!("{GITHUB_RAW}/{name}"))
+    }
+
+    fn owns_url(&self, url: &str) -> bool {
+        url.starts_with(&format!("{GITHUB_RAW}/")) && gitignore_path(url).is_none()
+    }
+}
+
+/// Returns the path of a template in the github/gitignore repository from its URL
+fn gitignore_path(url: &str) -> Option<&str> {
+    url.strip_prefix(GITHUB_RAW)?
+        .strip_prefix("/github/gitignore/main/")
+}
@@ -0,0 +1,63 @@
+use crate::config::settings::Settings;
+use crate::template::collection::forge::{forge_templates, RepoPath};
+use crate::template::collection::gitlab::{gl_raw_url, gl_tree, CACHE_PATH, GITLAB_URL};
+use crate::template::collection::TemplateCollectionKind;
+use crate::template::item::Template;
+use crate::template::provider::TemplateProvider;
+use anyhow::Result;
+
+/// Files from a GitLab repository, on gitlab.com or a self-hosted instance
+#[derive(Debug)]
+pub struct GitLabProvider;
+
+impl GitLabProvider {
+    /// Returns the configured GitLab base URL
+    fn base_url(&self) -> Result<String> {
+        Ok(Settings::get()?.gitlab.base_url(GITLAB_URL))
+    }
+}
+
+impl TemplateProvider for GitLabProvider {
+    fn prefix(&self) -> &str {
+        "gl:"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["gitlab:"]
+    }
+
+    fn title(&self) -> &str {
+        "GitLab"
+    }
+
+    fn url(&self, name: &str) -> Result<String> {
+        gl_raw_url(&self.base_url()?, &RepoPath::parse(name, true)?)
+    }
+
+    fn collections(&self) -> Vec<TemplateCollectionKind> {
+        let collections = match Settings::get() {
+            Ok(settings) => settings.gitlab.collections.as_slice(),
+            Err(_) => &[],
+        };
+        collections
+            .iter()
+            .map(|c| TemplateCollectionKind::new(self, c, &format!("{}: {c}", self.title())))
+            .collect()
+    }
+
+    fn list(&self, collection: &str) -> Result<Vec<Template>> {
+        let base = self.base_url()?;
+        forge_templates(self, CACHE_PATH, &base, collection, |repo| {
+            gl_tree(&base, repo)
+        })
+    }
+
+    fn owns_url(&self, url: &str) -> bool {
+        self.base_url()
+            .is_ok_and(|base| url.starts_with(&format!("{base}/api/v4/projects/")))
+    }
+
+    fn cache_files(&self) -> Vec<String> {
+        vec![CACHE_PATH.to_string()]
+    }
+}
@@ -0,0 +1,141 @@
+use crate::config::settings::{LocalCollection, Settings};
+use crate::template::collection::TemplateCollectionKind;
+use crate::template::item::Template;
+use crate::template::provider::{strip_provider_prefix, TemplateProvider};
+use anyhow::{anyhow, bail, Context, Result};
+use ignore::WalkBuilder;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Templates from the local directories configured as collections
+#[derive(Debug)]
+pub struct LocalProvider;
+
+impl LocalProvider {
+    /// Returns the configured local collection with the given name
+    fn collection(&self, name: &str) -> Result<&'static LocalCollection> {
+        Settings::get()?
+            .local
+            .iter()
+            .find(|c| c.name == name)
+            .ok_or_else(|| anyhow!("[Ignore Template] Unknown local collection: {name}"))
+    }
+
+    /// Returns the path of the template file in the collection directory, rejecting names
+    /// that would resolve outside of it
+    pub fn template_path(collection: &LocalCollection, template: &str) -> Result<PathBuf> {
+        let file = format!("{template}.gitignore");
+        let is_contained = Path::new(&file)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+        if !is_contained {
+            bail!("[Ignore Template] Local template is outside of the collection: {template}")
+        }
+
+        let path = collection.dir().join(file);
+        if !path.is_file() {
+            let path = path.to_str().unwrap_or("...unknown path");
+            bail!("[Ignore Template] Invalid or non-existent file path: {path}")
+        }
+        Ok(path)
+    }
+
+    /// Returns the names of the templates in the collection directory, being their paths
+    /// relative to it without the .gitignore extension
+    pub fn template_names(collection: &LocalCollection) -> Result<Vec<String>> {
+        let dir = collection.dir();
+        let dir_str = dir.to_str().unwrap_or("...unknown path");
+        if !dir.is_dir() {
+            bail!(
+                "Local template collection '{}' directory does not exist\n{dir_str}",
+                collection.name
+            )
+        }
+
+        // Hidden files and directories such as .git are skipped, but ignore files are not
+        // applied as the templates themselves might be ignored in the directory
+        let walker = WalkBuilder::new(&dir)
+            .standard_filters(false)
+            .hidden(true)
+            .build();
+
+        let mut names: Vec<String> = Vec::new();
+        for entry in walker {
+            let entry = entry.with_context(|| {
+                format!("Failed to read local template collection directory\n{dir_str}")
+            })?;
+            let path = match entry.path().strip_prefix(&dir) {
+                Ok(path) if entry.file_type().is_some_and(|t| t.is_file()) => path,
+                _ => continue,
+            };
+            let path = path
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            if let Some(name) = path.strip_suffix(".gitignore") {
+                names.push(name.to_string());
+            }
+        }
+        names.sort_by_key(|name| name.to_lowercase());
+        Ok(names)
+    }
+}
+
+impl TemplateProvider for LocalProvider {
+    fn prefix(&self) -> &str {
+        "local:"
+    }
+
+    fn title(&self) -> &str {
+        "Local"
+    }
+
+    fn name(&self, input: &str) -> String {
+        let name = strip_provider_prefix(self, input);
+        name.strip_suffix(".gitignore").unwrap_or(name).to_string()
+    }
+
+    fn url(&self, name: &str) -> Result<String> {
+        let (collection, template) = match name.split_once('/') {
+            Some((collection, template)) if !template.is_empty() => (collection, template),
+            _ => bail!(
+                "[Ignore Template] Invalid local template: {name}\nExpected: <collection>/<name>"
+            ),
+        };
+        let path = Self::template_path(self.collection(collection)?, template)?;
+        Ok(path.to_str().unwrap_or("...unknown path").to_string())
+    }
+
+    fn fetch(&self, name: &str) -> Result<String> {
+        let path = self.url(name)?;
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read ignore file template at path\n{path}"))?;
+        Ok(content)
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    fn collections(&self) -> Vec<TemplateCollectionKind> {
+        let collections = match Settings::get() {
+            Ok(settings) => settings.local.as_slice(),
+            Err(_) => &[],
+        };
+        collections
+            .iter()
+            .map(|c| {
+                TemplateCollectionKind::new(self, &c.name, &format!("{}: {}", self.title(), c.name))
+            })
+            .collect()
+    }
+
+    fn list(&self, collection: &str) -> Result<Vec<Template>> {
+        let names = Self::template_names(self.collection(collection)?)?;
+        names
+            .iter()
+            .map(|name| Template::new(&format!("{}{collection}/{name}", self.prefix())))
+            .collect()
+    }
+}
@@ -0,0 +1,154 @@
+pub mod bitbucket;
+pub mod file;
+pub mod gitea;
+pub mod github;
+pub mod gitlab;
+pub mod local;
+pub mod toptal;
+pub mod url;
+
+use crate::template::collection::TemplateCollectionKind;
+use crate::template::item::{fetch_url, Template};
+use crate::template::provider::bitbucket::BitbucketProvider;
+use crate::template::provider::file::FileProvider;
+use crate::template::provider::gitea::GiteaProvider;
+use crate::template::provider::github::{
+    GitHubCommunityProvider, GitHubGlobalProvider, GitHubProvider, GitHubRepoProvider,
+};
+use crate::template::provider::gitlab::GitLabProvider;
+use crate::template::provider::local::LocalProvider;
+use crate::template::provider::toptal::TopTalProvider;
+use crate::template::provider::url::UrlProvider;
+use crate::util::string::strip_prefixes;
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+
+/// Registered template providers, in the order their collections are displayed
+static PROVIDERS: Lazy<Vec<Box<dyn TemplateProvider>>> = Lazy::new(|| {
+    vec![
+        Box::new(TopTalProvider),
+        Box::new(GitHubProvider),
+        Box::new(GitHubGlobalProvider),
+        Box::new(GitHubCommunityProvider),
+        Box::new(GitHubRepoProvider),
+        Box::new(GitLabProvider),
+        Box::new(GiteaProvider),
+        Box::new(BitbucketProvider),
+        Box::new(LocalProvider),
+        Box::new(UrlProvider),
+        Box::new(FileProvider),
+    ]
+});
+
+/// A source of .gitignore templates
+pub trait TemplateProvider: Send + Sync {
+    /// The prefix identifying the provider in template arguments, e.g. "gh:"
+    fn prefix(&self) -> &str;
+
+    /// Alternative prefixes accepted in template arguments, e.g. "gitlab:"
+    fn aliases(&self) -> &[&str] {
+        &[]
+    }
+
+    /// The display name of the provider used in template titles, e.g. "GitHub"
+    fn title(&self) -> &str;
+
+    /// Returns how confident the provider is that an argument without a prefix belongs to it,
+    /// the provider with the highest confidence is used. `None` means it doesn't belong to it.
+    fn guess(&self, _input: &str) -> Option<u8> {
+        None
+    }
+
+    /// Returns the name of the template from the argument, stripping any prefixes or suffixes
+    fn name(&self, input: &str) -> String {
+        strip_provider_prefix(self, input).to_string()
+    }
+
+    /// Returns the URL (or file path) the template content is fetched from
+    fn url(&self, name: &str) -> Result<String>;
+
+    /// Fetches the template content
+    fn fetch(&self, name: &str) -> Result<String> {
+        fetch_url(&self.url(name)?, true)
+    }
+
+    /// Whether the template content is read from the local filesystem rather than fetched
+    fn is_local(&self) -> bool {
+        false
+    }
+
+    /// The template collections listed by the provider
+    fn collections(&self) -> Vec<TemplateCollectionKind> {
+        Vec::new()
+    }
+
+    /// Lists the templates in one of the provider's collections
+    fn list(&self, collection: &str) -> Result<Vec<Template>> {
+        Err(anyhow!(
+            "Template provider '{}' has no collection '{collection}'",
+            self.title()
+        ))
+    }
+
+    /// Whether content cached under the URL was fetched by the provider
+    fn owns_url(&self, _url: &str) -> bool {
+        false
+    }
+
+    /// The cache files used to store the provider's collections
+    fn cache_files(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Returns all the registered template providers
+pub fn providers() -> impl Iterator<Item = &'static dyn TemplateProvider> {
+    PROVIDERS.iter().map(|p| p.as_ref())
+}
+
+/// Returns the provider with the given prefix or alias, the trailing ':' is optional
+pub fn provider(prefix: &str) -> Result<&'static dyn TemplateProvider> {
+    let name = prefix.strip_suffix(':').unwrap_or(prefix);
+    providers()
+        .find(|p| {
+            [p.prefix()]
+                .into_iter()
+                .chain(p.aliases().iter().copied())
+                .any(|p| p.strip_suffix(':').unwrap_or(p) == name)
+        })
+        .ok_or_else(|| anyhow!("Unknown template provider: {prefix}"))
+}
+
+/// Returns the provider the content cached under the URL was fetched by
+pub fn provider_for_url(url: &str) -> Option<&'static dyn TemplateProvider> {
+    providers().find(|p| p.owns_url(url))
+}
+
+/// Returns the provider for a template argument, using its prefix or guessing it otherwise.
+///
+/// Defaults to the GitHub provider if no provider can be guessed.
+pub fn provider_for(input: &str) -> &'static dyn TemplateProvider {
+    // Match with prefix
+    if let Some(provider) = providers().find(|p| strip_provider_prefix(*p, input) != input) {
+        return provider;
+    }
+
+    // Match with best effort
+    providers()
+        .filter_map(|p| p.guess(input).map(|confidence| (confidence, p)))
+        .max_by_key(|(confidence, _)| *confidence)
+        .map(|(_, p)| p)
+        .unwrap_or(&GitHubProvider)
+}
+
+/// Strips the provider prefix or one of its aliases from a template argument
+pub fn strip_provider_prefix<'a, P: TemplateProvider + ?Sized>(
+    provider: &P,
+    input: &'a str,
+) -> &'a str {
+    let prefixes = [provider.prefix()]
+        .into_iter()
+        .chain(provider.aliases().iter().copied())
+        .collect::<Vec<_>>();
+    strip_prefixes(input, &prefixes).unwrap_or(input)
+}
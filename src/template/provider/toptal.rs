@@ -0,0 +1,47 @@
+use crate::template::collection::toptal::{TopTalTemplates, CACHE_PATH, TOPTAL_API};
+use crate::template::collection::TemplateCollectionKind;
+use crate::template::item::Template;
+use crate::template::provider::TemplateProvider;
+use crate::util::string::strip_suffixes;
+use anyhow::Result;
+
+/// Templates from the TopTal gitignore API
+#[derive(Debug)]
+pub struct TopTalProvider;
+
+impl TemplateProvider for TopTalProvider {
+    fn prefix(&self) -> &str {
+        "tt:"
+    }
+
+    fn title(&self) -> &str {
+        "TopTal"
+    }
+
+    fn name(&self, input: &str) -> String {
+        let name = input.strip_prefix(self.prefix()).unwrap_or(input);
+        strip_suffixes(name, &[".gitignore", ".patch", ".stack"])
+            .unwrap_or(name)
+            .to_string()
+    }
+
+    fn url(&self, name: &str) -> Result<String> {
+        Ok(format!("{TOPTAL_API}/{name}"))
+    }
+
+    fn collections(&self) -> Vec<TemplateCollectionKind> {
+        vec![TemplateCollectionKind::new(self, "toptal", self.title())]
+    }
+
+    fn list(&self, _collection: &str) -> Result<Vec<Template>> {
+        TopTalTemplates::templates()
+    }
+
+    fn owns_url(&self, url: &str) -> bool {
+        url.starts_with(&format!("{TOPTAL_API}/"))
+    }
+
+    fn cache_files(&self) -> Vec<String> {
+        vec![CACHE_PATH.to_string()]
+    }
+}
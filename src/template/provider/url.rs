@@ -0,0 +1,34 @@
+use crate::template::provider::{providers, TemplateProvider};
+use anyhow::{Context, Result};
+use url::Url;
+
+/// Templates from a remote URL to a text file
+#[derive(Debug)]
+pub struct UrlProvider;
+
+impl TemplateProvider for UrlProvider {
+    fn prefix(&self) -> &str {
+        "url:"
+    }
+
+    fn title(&self) -> &str {
+        "URL"
+    }
+
+    fn guess(&self, input: &str) -> Option<u8> {
+        Url::parse(input).is_ok().then_some(40)
+    }
+
+    fn url(&self, name: &str) -> Result<String> {
+        let url =
+            Url::parse(name).with_context(|| format!("[Ignore Template] Invalid URL: {name}"))?;
+        Ok(url.to_string())
+    }
+
+    fn owns_url(&self, url: &str) -> bool {
+        // Any other URL not fetched by one of the other providers
+        providers()
+            .filter(|p| p.prefix() != self.prefix())
+            .all(|p| !p.owns_url(url))
+    }
+}
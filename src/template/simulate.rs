@@ -0,0 +1,166 @@
+use crate::template::pattern::Rules;
+use crate::util::git::Repository;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// The effect of replacing the rules of an ignore file on the files in its directory.
+///
+/// Paths are relative to the ignore file directory and use '/' as the separator. Directories
+/// that become ignored or un-ignored as a whole are listed once with a trailing '/' instead of
+/// their files.
+#[derive(Debug, Default, Serialize)]
+pub struct Simulation {
+    /// Number of untracked files evaluated against the rules
+    pub files: usize,
+    /// Untracked files and directories ignored by the new rules but not by the current ones
+    pub ignored: Vec<String>,
+    /// Untracked files and directories ignored by the current rules but not by the new ones
+    pub unignored: Vec<String>,
+    /// Files tracked by git that the new rules match. Ignore rules have no effect on tracked
+    /// files, so they stay tracked until removed from the index.
+    pub tracked: Vec<String>,
+}
+
+impl Simulation {
+    /// Evaluates the current and new content of the ignore file against the files in the root
+    /// directory, along with the files tracked by the git repository containing it
+    pub fn run(root: &Path, current: &str, new: &str) -> Result<Self> {
+        let current = Rules::parse(current);
+        let new = Rules::parse(new);
+        let tracked = tracked_files(root)?;
+
+        let mut simulation = Self {
+            tracked: tracked
+                .iter()
+                .filter(|path| new.explain(path, false).ignored)
+                .cloned()
+                .collect(),
+            ..Default::default()
+        };
+        simulation.tracked.sort();
+
+        let tracked: HashSet<String> = tracked.into_iter().collect();
+        let mut walk = Walk {
+            current: &current,
+            new: &new,
+            tracked: &tracked,
+            simulation: &mut simulation,
+            unignored_files: 0,
+        };
+        walk.dir(root, "", false, false)?;
+
+        Ok(simulation)
+    }
+
+    /// Checks if the new rules change anything
+    pub fn is_empty(&self) -> bool {
+        self.ignored.is_empty() && self.unignored.is_empty() && self.tracked.is_empty()
+    }
+}
+
+/// Returns the tracked files in the root directory, relative to it
+fn tracked_files(root: &Path) -> Result<Vec<String>> {
+    let repository = match Repository::discover(root) {
+        Some(repository) => repository,
+        None => return Ok(Vec::new()),
+    };
+    let prefix = match root.strip_prefix(&repository.root) {
+        Ok(prefix) if prefix.as_os_str().is_empty() => String::new(),
+        Ok(prefix) => format!("{}/", to_slash_path(prefix)),
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    Ok(repository
+        .tracked_files()?
+        .into_iter()
+        .filter_map(|path| path.strip_prefix(&prefix).map(str::to_string))
+        .collect())
+}
+
+fn to_slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Walks the directory tree evaluating the untracked files against both rule sets
+struct Walk<'a> {
+    current: &'a Rules,
+    new: &'a Rules,
+    tracked: &'a HashSet<String>,
+    simulation: &'a mut Simulation,
+    /// Number of files un-ignored so far, as directories are listed instead of their files
+    unignored_files: usize,
+}
+
+impl Walk<'_> {
+    /// Evaluates the entries of a directory, given whether the directory is ignored by the
+    /// current and new rules. Ignored directories are only walked if the other rules don't
+    /// ignore them, as nothing inside an ignored directory can be un-ignored.
+    fn dir(&mut self, dir: &Path, prefix: &str, current: bool, new: bool) -> Result<()> {
+        let mut entries = fs::read_dir(dir)
+            .with_context(|| {
+                format!(
+                    "Failed to read directory at path\n{}",
+                    dir.to_str().unwrap_or("...unknown path")
+                )
+            })?
+            .filter_map(|entry| entry.ok())
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name == ".git" {
+                continue;
+            }
+            let path = format!("{prefix}{name}");
+            // Symlinks aren't followed, git tracks them as files
+            let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+
+            let was_ignored = current || self.current.ignores(&path, is_dir);
+            let is_ignored = new || self.new.ignores(&path, is_dir);
+            if is_dir {
+                match (was_ignored, is_ignored) {
+                    (true, true) => {}
+                    (false, true) => self.simulation.ignored.push(format!("{path}/")),
+                    (true, false) => {
+                        let (files, unignored) = (self.simulation.files, self.unignored_files);
+                        let len = self.simulation.unignored.len();
+                        self.dir(&entry.path(), &format!("{path}/"), true, false)?;
+
+                        // List the directory instead if all of its files are un-ignored
+                        let files = self.simulation.files - files;
+                        if files > 0 && files == self.unignored_files - unignored {
+                            self.simulation.unignored.truncate(len);
+                            self.simulation.unignored.push(format!("{path}/"));
+                        }
+                    }
+                    (false, false) => {
+                        self.dir(&entry.path(), &format!("{path}/"), false, false)?;
+                    }
+                }
+                continue;
+            }
+
+            if self.tracked.contains(&path) {
+                continue;
+            }
+            self.simulation.files += 1;
+            match (was_ignored, is_ignored) {
+                (false, true) => self.simulation.ignored.push(path),
+                (true, false) => {
+                    self.simulation.unignored.push(path);
+                    self.unignored_files += 1;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -246,3 +246,1179 @@ mod ignore_template {
         }
     }
 }
+
+#[cfg(test)]
+mod managed_block {
+    use crate::template::block::ManagedBlock;
+
+    #[test]
+    fn appends_to_existing_file() {
+        let existing = "# Hand written\n/secrets\n";
+        let merged = ManagedBlock::merge(existing, "/target").unwrap();
+        assert!(merged.starts_with(existing));
+        assert_eq!(
+            ManagedBlock::extract(&merged).unwrap(),
+            Some("/target".to_string())
+        );
+    }
+
+    #[test]
+    fn replaces_only_the_managed_block() {
+        let before = "# Above\r\n/secrets\r\n\r\n";
+        let after = "\n# Below\n!keep.me";
+        let existing = format!("{before}{}\n{after}", ManagedBlock::wrap("/target"));
+        let merged = ManagedBlock::merge(&existing, "/dist").unwrap();
+        assert!(merged.starts_with(before));
+        assert!(merged.ends_with(after));
+        assert_eq!(
+            ManagedBlock::extract(&merged).unwrap(),
+            Some("/dist".to_string())
+        );
+    }
+
+    #[test]
+    fn remerging_is_stable() {
+        let once = ManagedBlock::merge("/secrets\n", "/target").unwrap();
+        let twice = ManagedBlock::merge(&once, "/target").unwrap();
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn missing_end_marker() {
+        let existing = format!("{}\n/target\n", ManagedBlock::begin());
+        assert!(ManagedBlock::merge(&existing, "/dist").is_err());
+    }
+}
+
+#[cfg(test)]
+mod manifest {
+    use crate::config::manifest::Manifest;
+    use std::path::Path;
+
+    #[test]
+    fn parse() {
+        let manifest: Manifest = toml::from_str(
+            r#"
+            templates = ["gh:Rust", "tt:jetbrains+all,ghg:Linux"]
+            output = "sub/.gitignore"
+            rules = ["/secrets"]
+            "#,
+        )
+        .unwrap();
+        let templates = manifest.templates().unwrap();
+        assert_eq!(
+            templates.specs().unwrap(),
+            vec!["gh:Rust", "tt:jetbrains+all", "ghg:Linux"]
+        );
+        assert_eq!(
+            manifest.output_path(Path::new("/repo/.gitnr.toml")),
+            Path::new("/repo/sub/.gitignore")
+        );
+    }
+
+    #[test]
+    fn unknown_fields() {
+        assert!(toml::from_str::<Manifest>("template = [\"gh:Rust\"]").is_err());
+    }
+
+    #[test]
+    fn inline_rules() {
+        let manifest = Manifest {
+            rules: vec!["/secrets".into(), "*.local".into()],
+            ..Default::default()
+        };
+        let content = manifest.with_rules("/target".into());
+        assert!(content.starts_with("/target\n\n###"));
+        assert!(content.ends_with("/secrets\n*.local"));
+    }
+}
+
+#[cfg(test)]
+mod lockfile {
+    use crate::config::lockfile::{content_hash, LockedTemplate, Lockfile, LockfileChange};
+
+    fn locked(spec: &str, hash: &str) -> LockedTemplate {
+        LockedTemplate {
+            spec: spec.to_string(),
+            url: format!("https://example.com/{spec}"),
+            commit: None,
+            hash: hash.to_string(),
+        }
+    }
+
+    #[test]
+    fn hash() {
+        assert_eq!(
+            content_hash("/target"),
+            content_hash(&String::from("/target"))
+        );
+        assert_ne!(content_hash("/target"), content_hash("/target/"));
+        assert!(content_hash("").starts_with("sha256:"));
+    }
+
+    #[test]
+    fn changes() {
+        let old = Lockfile {
+            templates: vec![locked("gh:Rust", "a"), locked("gh:Go", "b")],
+            ..Default::default()
+        };
+        let new = Lockfile {
+            templates: vec![locked("gh:Rust", "c"), locked("tt:Python", "d")],
+            ..Default::default()
+        };
+        assert_eq!(
+            old.changes(&new),
+            vec![
+                LockfileChange::Updated(locked("gh:Rust", "a"), locked("gh:Rust", "c")),
+                LockfileChange::Added(locked("tt:Python", "d")),
+                LockfileChange::Removed(locked("gh:Go", "b")),
+            ]
+        );
+        assert!(new.changes(&new).is_empty());
+    }
+
+    #[test]
+    fn merge() {
+        let current = Lockfile {
+            templates: vec![locked("gh:Rust", "a"), locked("gh:Go", "b")],
+            ..Default::default()
+        };
+        let resolved = Lockfile {
+            templates: vec![locked("gh:Rust", "c"), locked("tt:Python", "d")],
+            ..Default::default()
+        };
+        let merged = current.merge(&resolved);
+        assert_eq!(
+            merged.templates,
+            vec![
+                locked("gh:Rust", "c"),
+                locked("gh:Go", "b"),
+                locked("tt:Python", "d"),
+            ]
+        );
+        // Updating one entry leaves the others unchanged
+        assert_eq!(
+            current.changes(&merged),
+            vec![
+                LockfileChange::Updated(locked("gh:Rust", "a"), locked("gh:Rust", "c")),
+                LockfileChange::Added(locked("tt:Python", "d")),
+            ]
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        let lockfile = Lockfile {
+            templates: vec![locked("gh:Rust", "a")],
+            ..Default::default()
+        };
+        let parsed: Lockfile = toml::from_str(&toml::to_string(&lockfile).unwrap()).unwrap();
+        assert_eq!(parsed, lockfile);
+    }
+}
+
+#[cfg(test)]
+mod check {
+    use crate::commands::check::{generated_section, is_up_to_date};
+    use crate::template::block::ManagedBlock;
+    use crate::util::diff::{diff_lines, DiffLine};
+
+    #[test]
+    fn whole_file() {
+        let actual = generated_section("/target\r\n\r\n", false).unwrap();
+        assert!(is_up_to_date(&actual, "/target"));
+        assert!(!is_up_to_date(&actual, "/target\n/dist"));
+    }
+
+    #[test]
+    fn managed_block() {
+        let existing = ManagedBlock::merge("# Mine\n/secrets\n", "/target").unwrap();
+        let actual = generated_section(&existing, true).unwrap();
+        assert!(is_up_to_date(&actual, "/target"));
+    }
+
+    #[test]
+    fn missing_managed_block() {
+        assert!(generated_section("/target\n", true).is_err());
+    }
+
+    #[test]
+    fn diff() {
+        let lines = diff_lines("/target\n*.log\n", "/target\n/dist\n", "old", "new");
+        let lines = lines.iter().map(DiffLine::text).collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                "--- old",
+                "+++ new",
+                "@@ -1,2 +1,2 @@",
+                " /target",
+                "-*.log",
+                "+/dist"
+            ]
+        );
+        assert!(diff_lines("/target\n", "/target\n", "old", "new").is_empty());
+    }
+}
+
+#[cfg(test)]
+mod detect {
+    use crate::config::settings::Settings;
+    use crate::template::detect::Detector;
+    use crate::util::string::wildcard_match;
+    use std::fs;
+
+    #[test]
+    fn wildcards() {
+        assert!(wildcard_match("*.csproj", "App.csproj"));
+        assert!(wildcard_match("Cargo.toml", "Cargo.toml"));
+        assert!(wildcard_match("?ar*.t*l", "Cargo.toml"));
+        assert!(!wildcard_match("*.csproj", "App.csproj.user"));
+        assert!(!wildcard_match("Cargo.toml", "cargo.toml"));
+    }
+
+    #[test]
+    fn markers() {
+        let root = std::env::temp_dir().join(format!("gitnr-test-detect-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("app/.vscode")).unwrap();
+        fs::create_dir_all(root.join(".idea")).unwrap();
+        fs::write(root.join("app/Cargo.toml"), "").unwrap();
+        fs::write(root.join("vscode"), "").unwrap();
+        fs::write(root.join("justfile"), "").unwrap();
+
+        let config = "[[detect]]\nmarkers = [\"justfile\"]\ntemplates = [\"tt:Just\"]\n";
+        let settings = Settings::parse(config, "config.toml").unwrap();
+        let detector = Detector::with_settings(&settings, 3).unwrap();
+        let detections = detector.detect(&root).unwrap();
+        let found = detections
+            .iter()
+            .map(|d| (d.template.spec().unwrap(), d.reason.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(found[0], ("tt:Just".to_string(), "found justfile"));
+        assert_eq!(found[1], ("gh:Rust".to_string(), "found app/Cargo.toml"));
+        assert!(found.iter().any(|(_, r)| *r == "found .idea/"));
+        assert!(found.iter().any(|(_, r)| *r == "found app/.vscode/"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod provider {
+    use crate::config::settings::LocalCollection;
+    use crate::template::item::Template;
+    use crate::template::provider::local::LocalProvider;
+    use std::fs;
+
+    fn resolve(input: &str) -> (String, String) {
+        let template = Template::new(input).unwrap();
+        (template.spec().unwrap(), template.value.url().unwrap())
+    }
+
+    #[test]
+    fn prefixes() {
+        let (spec, url) = resolve("tt:Python.stack");
+        assert_eq!(spec, "tt:Python");
+        assert_eq!(
+            url,
+            "https://www.toptal.com/developers/gitignore/api/Python"
+        );
+
+        let (spec, url) = resolve("ghc:Linux/Snap");
+        assert_eq!(spec, "ghc:Linux/Snap");
+        assert_eq!(
+            url,
+            "https://raw.githubusercontent.com/github/gitignore/main/community/Linux/Snap.gitignore"
+        );
+    }
+
+    #[test]
+    fn guesses() {
+        assert_eq!(resolve("Rust.gitignore").0, "gh:Rust");
+        assert_eq!(resolve("global/Linux").0, "ghg:Linux");
+        assert_eq!(resolve("Community/OpenSSL").0, "ghc:OpenSSL");
+        assert_eq!(
+            resolve("github/gitignore/main/Rust.gitignore").0,
+            "repo:github/gitignore/main/Rust.gitignore"
+        );
+        assert_eq!(
+            resolve("https://example.com/a/b/c").0,
+            "url:https://example.com/a/b/c"
+        );
+        assert_eq!(resolve("Cargo.toml").0, "file:Cargo.toml");
+    }
+
+    #[test]
+    fn forges() {
+        let (spec, url) = resolve("gitlab:group/project/main/templates/Rust.gitignore");
+        assert_eq!(spec, "gl:group/project/main/templates/Rust.gitignore");
+        assert_eq!(
+            url,
+            "https://gitlab.com/api/v4/projects/group%2Fproject/repository/files/templates%2FRust.gitignore/raw?ref=main"
+        );
+
+        let (_, url) = resolve("gitea:owner/repo/v1.0/templates/Rust.gitignore");
+        assert_eq!(
+            url,
+            "https://gitea.com/api/v1/repos/owner/repo/raw/templates/Rust.gitignore?ref=v1.0"
+        );
+
+        let (_, url) = resolve("bb:owner/repo/main/Rust.gitignore");
+        assert_eq!(
+            url,
+            "https://api.bitbucket.org/2.0/repositories/owner/repo/src/main/Rust.gitignore"
+        );
+
+        let template = Template::new("gl:group/project/main").unwrap();
+        assert!(template.value.url().is_err());
+    }
+
+    #[test]
+    fn local() {
+        let template = Template::new("local:team/lang/Rust.gitignore").unwrap();
+        assert_eq!(template.spec().unwrap(), "local:team/lang/Rust");
+        assert_eq!(template.value.title().unwrap(), "Local: team/lang/Rust");
+        assert!(Template::new("local:unknown/Rust")
+            .unwrap()
+            .value
+            .url()
+            .is_err());
+    }
+
+    #[test]
+    fn local_collection() {
+        let root = std::env::temp_dir().join(format!("gitnr-test-local-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let dir = root.join("team");
+        fs::create_dir_all(dir.join("lang")).unwrap();
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        fs::write(dir.join("lang/Rust.gitignore"), "/target\n").unwrap();
+        fs::write(dir.join("Node.gitignore"), "node_modules/\n").unwrap();
+        fs::write(dir.join(".git/Hidden.gitignore"), "").unwrap();
+        fs::write(dir.join("readme.md"), "").unwrap();
+        fs::write(root.join("Outside.gitignore"), "").unwrap();
+
+        let collection = LocalCollection {
+            name: "team".to_string(),
+            path: dir.to_str().unwrap().to_string(),
+        };
+        let names = LocalProvider::template_names(&collection).unwrap();
+        assert_eq!(names, vec!["lang/Rust", "Node"]);
+
+        let path = LocalProvider::template_path(&collection, "lang/Rust").unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "/target\n");
+        assert!(LocalProvider::template_path(&collection, "lang/Go").is_err());
+        assert!(LocalProvider::template_path(&collection, "../Outside").is_err());
+        assert!(LocalProvider::template_path(&collection, "lang/../../Node").is_err());
+        assert!(LocalProvider::template_path(&collection, "/etc/hosts").is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod credentials {
+    use crate::config::settings::{Settings, Token};
+
+    #[test]
+    fn parse() {
+        let settings = Settings::parse(
+            "[github]\ntoken = \"ghp_secret\"\n\n[credentials]\n\"git.example.com\" = \"glpat-secret\"\n",
+            "config.toml",
+        )
+        .unwrap();
+        assert_eq!(settings.github.token, Some(Token::new("ghp_secret")));
+        assert_eq!(
+            settings.credentials.get("git.example.com"),
+            Some(&Token::new("glpat-secret"))
+        );
+    }
+
+    #[test]
+    fn hidden() {
+        let token = Token::new("ghp_secret");
+        assert!(!format!("{token:?}").contains("secret"));
+
+        let error = Settings::parse(
+            "[gitlab]\ntoken = \"glpat-secret\"\ncollections = \"glpat-secret\"\n",
+            "config.toml",
+        )
+        .unwrap_err();
+        let error = format!("{error:?}");
+        assert!(!error.contains("secret"));
+        assert!(error.contains("line 3"));
+    }
+}
+
+#[cfg(test)]
+mod cache {
+    use crate::config::settings::{CacheKind, Settings};
+    use crate::template::item::Template;
+    use crate::util::string::format_size;
+    use crate::util::time::{format_duration, parse_duration, Ttl};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(
+            parse_duration("12h").unwrap(),
+            Duration::from_secs(12 * 60 * 60)
+        );
+        assert_eq!(
+            parse_duration("2w").unwrap(),
+            Duration::from_secs(14 * 24 * 60 * 60)
+        );
+        assert!(parse_duration("7").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("7y").is_err());
+
+        let overflow = format!("{}w", u64::MAX / 60);
+        let err = parse_duration(&overflow).unwrap_err().to_string();
+        assert!(err.contains("too large"), "{err}");
+
+        assert_eq!(format_duration(Duration::from_secs(45)), "45s");
+        assert_eq!(format_duration(Duration::from_secs(90 * 60)), "1h");
+        assert_eq!(format_duration(parse_duration("7d").unwrap()), "7d");
+    }
+
+    #[test]
+    fn ttl() {
+        let hours = |h: u64| Ttl::After(Duration::from_secs(h * 60 * 60));
+        let mut settings = Settings::parse(
+            "[cache]\ntemplates = \"12h\"\n\n[cache.provider.gh]\ncollections = \"never\"\n\n[cache.provider.tt]\nttl = \"2h\"\n",
+            "config.toml",
+        )
+        .unwrap();
+        let cache = &settings.cache;
+        assert_eq!(cache.ttl("gh:", CacheKind::Collections), Ttl::Never);
+        assert_eq!(cache.ttl("gh:", CacheKind::Templates), hours(12));
+        assert_eq!(cache.ttl("tt:", CacheKind::Templates), hours(2));
+        assert_eq!(cache.ttl("gl:", CacheKind::Collections), hours(1));
+
+        settings.cache.env = Some(hours(3));
+        assert_eq!(settings.cache.ttl("gh:", CacheKind::Collections), hours(3));
+
+        let day_old = SystemTime::now() - Duration::from_secs(24 * 60 * 60);
+        assert!(hours(12).is_expired(day_old));
+        assert!(!Ttl::Never.is_expired(day_old));
+        assert!(Settings::parse("[cache]\nttl = \"soon\"\n", "config.toml").is_err());
+    }
+
+    #[test]
+    fn json_files() {
+        use crate::util::fs::{is_parse_error, read_json_file, update_json_file, write_json_file};
+
+        let dir = std::env::temp_dir().join(format!("gitnr-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("cache.json");
+        let path = path.to_str().unwrap();
+
+        write_json_file(path, &vec![1]).unwrap();
+        update_json_file(path, |saved: Option<Vec<u32>>| {
+            let mut saved = saved.unwrap_or_default();
+            saved.push(2);
+            saved
+        })
+        .unwrap();
+        assert_eq!(read_json_file::<Vec<u32>>(path).unwrap(), vec![1, 2]);
+
+        // Only the file and its lock file are left, without any temporary files
+        let mut files = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(files, vec!["cache.json", "cache.json.lock"]);
+
+        // Only content that can't be parsed is quarantined, not files that can't be read
+        std::fs::write(path, "{").unwrap();
+        assert!(is_parse_error(
+            &read_json_file::<Vec<u32>>(path).unwrap_err()
+        ));
+        let missing = dir.join("missing.json");
+        let error = read_json_file::<Vec<u32>>(missing.to_str().unwrap()).unwrap_err();
+        assert!(!is_parse_error(&error));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn owners() {
+        use crate::template::provider::provider_for_url;
+
+        let owner = |input: &str| {
+            let url = Template::new(input).unwrap().value.url().unwrap();
+            provider_for_url(&url).map(|p| p.prefix())
+        };
+        assert_eq!(owner("gh:Rust"), Some("gh:"));
+        assert_eq!(owner("ghg:Linux"), Some("ghg:"));
+        assert_eq!(owner("ghc:Linux/Snap"), Some("ghc:"));
+        assert_eq!(owner("tt:Python"), Some("tt:"));
+        assert_eq!(owner("repo:owner/repo/main/Rust.gitignore"), Some("repo:"));
+        assert_eq!(owner("gl:group/project/main/Rust.gitignore"), Some("gl:"));
+        assert_eq!(
+            owner("gitea:owner/repo/main/Rust.gitignore"),
+            Some("gitea:")
+        );
+        assert_eq!(owner("bb:owner/repo/main/Rust.gitignore"), Some("bb:"));
+        assert_eq!(
+            owner("url:https://example.com/Rust.gitignore"),
+            Some("url:")
+        );
+    }
+
+    #[test]
+    fn clear_and_prune() {
+        use crate::commands::cache::{clear_collections, prune_collections};
+        use crate::template::provider::provider;
+
+        let dir =
+            std::env::temp_dir().join(format!("gitnr-test-collections-{}", std::process::id()));
+        let collections = dir.join("collections");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&collections).unwrap();
+        for name in [
+            "github.json",
+            "toptal.json",
+            "gitlab.json",
+            "gitlab.json.corrupt",
+        ] {
+            std::fs::write(collections.join(name), "{}").unwrap();
+        }
+        let day_old = SystemTime::now() - Duration::from_secs(24 * 60 * 60);
+        std::fs::File::options()
+            .write(true)
+            .open(collections.join("toptal.json"))
+            .unwrap()
+            .set_modified(day_old)
+            .unwrap();
+
+        let removed = clear_collections(&dir, provider("ghg").unwrap()).unwrap();
+        assert_eq!(removed, vec!["collections/github.json"]);
+        assert!(clear_collections(&dir, provider("gh").unwrap())
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(
+            prune_collections(&dir, Duration::from_secs(60 * 60)).unwrap(),
+            1
+        );
+        assert!(collections.join("gitlab.json").is_file());
+        assert!(!collections.join("toptal.json").exists());
+
+        // Files that aren't collections, such as quarantined ones, are never removed
+        assert_eq!(prune_collections(&dir, Duration::ZERO).unwrap(), 1);
+        assert!(collections.join("gitlab.json.corrupt").is_file());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
+
+#[cfg(test)]
+mod offline {
+    use crate::template::bundled::find_bundled;
+    use crate::util::fs::{fetch_or_stale, is_offline, set_auto_offline, set_offline};
+    use crate::util::http::http;
+    use anyhow::Context;
+    use std::sync::Mutex;
+
+    /// Serializes the tests changing the offline mode, which is shared by the whole process
+    static OFFLINE_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Runs the test with the offline mode set, restoring the previous mode afterwards
+    pub(super) fn with_offline(offline: bool, test: impl FnOnce()) {
+        let _lock = OFFLINE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let previous = is_offline();
+        set_offline(offline);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(test));
+        set_offline(previous);
+        set_auto_offline(true);
+        if let Err(e) = result {
+            std::panic::resume_unwind(e);
+        }
+    }
+
+    /// Fails with a network error by connecting to a local port nothing listens on
+    fn unreachable() -> anyhow::Result<String> {
+        http()
+            .get("http://127.0.0.1:9")
+            .call()
+            .with_context(|| "Failed to fetch")?;
+        Ok("fetched".to_string())
+    }
+
+    #[test]
+    fn stale_fallback() {
+        with_offline(false, || {
+            let content = fetch_or_stale("test content", Some("stale".to_string()), unreachable);
+            assert_eq!(content.unwrap(), "stale");
+            assert!(is_offline());
+        });
+        with_offline(false, || {
+            let content =
+                fetch_or_stale::<String>("test content", None, || anyhow::bail!("Not found"));
+            assert!(content.is_err());
+            assert!(!is_offline());
+        });
+    }
+
+    #[test]
+    fn stay_online() {
+        with_offline(false, || {
+            set_auto_offline(false);
+            let content = fetch_or_stale("test content", Some("stale".to_string()), unreachable);
+            assert_eq!(content.unwrap(), "stale");
+            assert!(fetch_or_stale("test content", None, unreachable).is_err());
+            assert!(!is_offline());
+        });
+    }
+
+    #[test]
+    fn not_cached() {
+        with_offline(true, || {
+            let content = fetch_or_stale("test content", None, unreachable);
+            let error = content.unwrap_err().to_string();
+            assert_eq!(
+                error,
+                "The test content is not cached and cannot be fetched while offline"
+            );
+        });
+        with_offline(false, || {
+            assert!(fetch_or_stale("test content", None, unreachable).is_err());
+        });
+    }
+
+    #[test]
+    fn bundled() {
+        let templates = [
+            ("Rust.gitignore", "/target\n"),
+            ("Global/Linux.gitignore", "*~"),
+        ];
+        let url = "https://raw.githubusercontent.com/github/gitignore/main";
+        assert_eq!(
+            find_bundled(&templates, &format!("{url}/Rust.gitignore")),
+            Some("/target")
+        );
+        assert_eq!(
+            find_bundled(&templates, &format!("{url}/Global/Linux.gitignore")),
+            Some("*~")
+        );
+        assert_eq!(
+            find_bundled(&templates, &format!("{url}/Go.gitignore")),
+            None
+        );
+        assert_eq!(
+            find_bundled(&templates, "https://example.com/Rust.gitignore"),
+            None
+        );
+    }
+}
+
+#[cfg(test)]
+mod revalidate {
+    use crate::template::item::cache::{TemplateCache, TemplateCacheItem};
+    use crate::template::item::fetch_url;
+    use crate::util::http::{call_revalidate, http_get, Revalidated, Validators};
+    use std::collections::HashMap;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+    use std::time::{Duration, SystemTime};
+
+    /// Serves the responses to the connections in order on a local port, returning its URL and
+    /// the handle joining to the received requests
+    fn serve(responses: Vec<&'static str>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                stream.write_all(response.as_bytes()).unwrap();
+                requests.push(String::from_utf8_lossy(&request).to_lowercase());
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn validators() {
+        let (url, server) = serve(vec![
+            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nLast-Modified: Wed, 21 Oct 2015 07:28:00 GMT\r\nContent-Length: 7\r\nConnection: close\r\n\r\n/target",
+            "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n",
+        ]);
+
+        let validators = match call_revalidate(http_get(&url), None).unwrap() {
+            Revalidated::Modified(res, validators) => {
+                assert_eq!(res.into_string().unwrap(), "/target");
+                validators
+            }
+            Revalidated::NotModified => panic!("Expected a modified response"),
+        };
+        assert_eq!(validators.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(
+            validators.last_modified.as_deref(),
+            Some("Wed, 21 Oct 2015 07:28:00 GMT")
+        );
+
+        let res = call_revalidate(http_get(&url), Some(&validators)).unwrap();
+        assert!(matches!(res, Revalidated::NotModified));
+
+        let requests = server.join().unwrap();
+        assert!(!requests[0].contains("if-none-match"));
+        assert!(requests[1].contains("if-none-match: \"v1\""));
+        assert!(requests[1].contains("if-modified-since: wed, 21 oct 2015 07:28:00 gmt"));
+    }
+
+    #[test]
+    fn not_modified() {
+        let (url, server) = serve(vec![
+            "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n",
+        ]);
+        let url = format!("{url}/Rust.gitignore");
+        let validators = Validators {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+        };
+        TemplateCache::set(&url, "/target", validators).unwrap();
+        let updated = |url: &str| {
+            let entries = TemplateCache::entries();
+            entries.into_iter().find(|e| e.key == url).unwrap().updated
+        };
+        let before = updated(&url);
+        thread::sleep(Duration::from_millis(10));
+
+        super::offline::with_offline(false, || {
+            assert_eq!(fetch_url(&url, false).unwrap(), "/target");
+        });
+        assert!(updated(&url) > before);
+        assert_eq!(TemplateCache::get_stale(&url).unwrap(), "/target");
+        assert!(server.join().unwrap()[0].contains("if-none-match: \"v1\""));
+    }
+
+    #[test]
+    fn round_trip() {
+        let item = TemplateCacheItem {
+            updated: SystemTime::now(),
+            content: "/target".to_string(),
+            validators: Validators {
+                etag: Some("\"v1\"".to_string()),
+                last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+            },
+        };
+        let cache = HashMap::from([("url".to_string(), item.clone())]);
+        let json = serde_json::to_string(&cache).unwrap();
+        let loaded: HashMap<String, TemplateCacheItem> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded["url"].validators, item.validators);
+        assert_eq!(loaded["url"].updated, item.updated);
+
+        // Caches saved before the validators were added are still read
+        let json = r#"{"url":{"updated":{"secs_since_epoch":0,"nanos_since_epoch":0},"content":"/target"}}"#;
+        let loaded: HashMap<String, TemplateCacheItem> = serde_json::from_str(json).unwrap();
+        assert_eq!(loaded["url"].validators, Validators::default());
+    }
+}
+
+#[cfg(test)]
+mod parallel {
+    use crate::util::parallel::{parallel_map, MAX_WORKERS};
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn order() {
+        let items = (0..30).collect::<Vec<u64>>();
+        let threads = Mutex::new(HashSet::new());
+        let results = parallel_map(&items, |i| {
+            threads.lock().unwrap().insert(thread::current().id());
+            // Later items finish first to check the results are still in order
+            thread::sleep(Duration::from_millis(30 - i));
+            i * 2
+        });
+        assert_eq!(results, items.iter().map(|i| i * 2).collect::<Vec<_>>());
+        assert!(threads.lock().unwrap().len() <= MAX_WORKERS);
+    }
+}
+
+#[cfg(test)]
+mod pattern {
+    use crate::template::pattern::{dedupe, Pattern, Rules};
+
+    fn lines(body: &str) -> Vec<&str> {
+        body.lines().filter(|l| !l.trim().is_empty()).collect()
+    }
+
+    #[test]
+    fn parse() {
+        let pattern = Pattern::parse("!/build/  ").unwrap();
+        assert!(pattern.negated && pattern.dir_only && pattern.anchored);
+        assert_eq!(pattern.glob, "build");
+
+        // Leading whitespace is part of the pattern, trailing spaces only when escaped
+        assert_eq!(Pattern::parse(" *.log").unwrap().glob, " *.log");
+        assert_eq!(Pattern::parse("*.log \t").unwrap().glob, "*.log \t");
+        assert_eq!(
+            Pattern::parse(" # not a comment").unwrap().glob,
+            " # not a comment"
+        );
+        assert!(!Pattern::parse("**/a/b").unwrap().anchored);
+        assert!(Pattern::parse("a/b").unwrap().anchored);
+        assert_eq!(Pattern::parse("foo\\ ").unwrap().glob, "foo\\ ");
+        assert_eq!(Pattern::parse("foo\\   ").unwrap().glob, "foo\\ ");
+        assert!(Pattern::parse("# comment").is_none());
+        assert!(Pattern::parse("   ").is_none());
+    }
+
+    #[test]
+    fn redundant() {
+        let deduped = dedupe(vec![
+            "# Logs\n*.log\ntarget\n".to_string(),
+            "# Logs\n *.log\n**/target\n/target\ntarget/\n/build\nbuild/\n".to_string(),
+        ]);
+        // Comments are kept, and the indented pattern only matches names with a leading space
+        assert_eq!(
+            lines(&deduped.bodies[1]),
+            vec!["# Logs", " *.log", "/build", "build/"]
+        );
+        assert!(deduped.conflicts.is_empty());
+
+        // Negations that can't match the same paths don't prevent removing duplicates
+        let deduped = dedupe(vec![
+            "*.log\n.vscode/*\n!.vscode/settings.json\n".to_string(),
+            "*.log\n".to_string(),
+        ]);
+        assert!(lines(&deduped.bodies[1]).is_empty());
+    }
+
+    #[test]
+    fn negations() {
+        let deduped = dedupe(vec![
+            "*.log\n!keep.log\n.env*\n!.env.example\n".to_string(),
+            "*.log\n.env*\n!.env.example\n*.tmp\n".to_string(),
+        ]);
+        // All are needed as they follow an overlapping pattern with the opposite effect
+        assert_eq!(
+            lines(&deduped.bodies[1]),
+            vec!["*.log", ".env*", "!.env.example", "*.tmp"]
+        );
+
+        // The later `*.log` re-ignores `keep.log`, while `.env.example` is un-ignored again
+        assert_eq!(deduped.conflicts.len(), 1);
+        assert_eq!(deduped.conflicts[0].negated, "!keep.log");
+        assert_eq!(deduped.conflicts[0].ignored, "*.log");
+        assert_eq!(
+            (
+                deduped.conflicts[0].negated_in,
+                deduped.conflicts[0].ignored_in
+            ),
+            (0, 1)
+        );
+    }
+
+    #[test]
+    fn matches() {
+        let matches = |line: &str, path: &str, is_dir: bool| {
+            Pattern::parse(line).unwrap().matches(path, is_dir)
+        };
+        assert!(matches("*.log", "a/b/app.log", false));
+        assert!(matches("/target", "target", true));
+        assert!(!matches("/target", "a/target", true));
+        assert!(matches("target/", "a/target", true));
+        assert!(!matches("target/", "a/target", false));
+        assert!(matches("a/**/b", "a/x/y/b", false));
+        assert!(matches("a/**/b", "a/b", false));
+        assert!(matches("foo/**", "foo/bar/baz", false));
+        assert!(!matches("foo/**", "foo", true));
+        assert!(!matches("a**b", "a/x/b", false));
+        assert!(matches("*.[oa]", "lib.a", false));
+        assert!(!matches("*.[!oa]", "lib.o", false));
+    }
+
+    #[test]
+    fn explain() {
+        let rules = Rules::parse(
+            "###  GitHub: Node  ###\n*.log\n!keep.log\nlogs/\n\n###  Local  ###\n/build\n",
+        );
+
+        let explanation = rules.explain("keep.log", false);
+        assert!(!explanation.ignored);
+        assert_eq!(explanation.matches.len(), 2);
+        assert_eq!(explanation.matches[1].line_number, 3);
+        assert_eq!(
+            explanation.matches[1].section.as_deref(),
+            Some("GitHub: Node")
+        );
+
+        let explanation = rules.explain("build", true);
+        assert!(explanation.ignored);
+        assert_eq!(explanation.matches[0].section.as_deref(), Some("Local"));
+
+        // A file in an ignored directory is ignored even if a rule un-ignores it
+        let explanation = rules.explain("src/logs/keep.log", false);
+        assert!(explanation.ignored);
+        let (parent, rule) = explanation.ignored_parent.unwrap();
+        assert_eq!((parent.as_str(), rule.line_number), ("src/logs", 4));
+
+        assert!(rules.explain("src/main.rs", false).matches.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod simulate {
+    use crate::template::simulate::Simulation;
+    use crate::util::git::parse_index;
+    use std::fs;
+
+    /// Builds a version 2 git index with the given paths
+    fn index(paths: &[&str]) -> Vec<u8> {
+        let mut data = b"DIRC".to_vec();
+        data.extend(2u32.to_be_bytes());
+        data.extend((paths.len() as u32).to_be_bytes());
+        for path in paths {
+            let start = data.len();
+            data.extend([0; 60]);
+            data.extend((path.len() as u16).to_be_bytes());
+            data.extend(path.as_bytes());
+            data.resize(start + ((62 + path.len() + 8) & !7), 0);
+        }
+        data
+    }
+
+    #[test]
+    fn git_index() {
+        let paths = parse_index(&index(&["Cargo.toml", "src/main.rs", "target/app"])).unwrap();
+        assert_eq!(paths, vec!["Cargo.toml", "src/main.rs", "target/app"]);
+        assert!(parse_index(b"not an index").is_err());
+    }
+
+    #[test]
+    fn git_index_varint() {
+        // A version 4 index with a single entry, its path prefixed with the given varint
+        let index_v4 = |varint: &[u8]| {
+            let mut data = b"DIRC".to_vec();
+            data.extend(4u32.to_be_bytes());
+            data.extend(1u32.to_be_bytes());
+            data.extend([0; 62]);
+            data.extend(varint);
+            data.extend(b"a.txt\0");
+            data
+        };
+        assert_eq!(parse_index(&index_v4(&[0])).unwrap(), vec!["a.txt"]);
+
+        let truncated = &index_v4(&[0x80])[..75];
+        assert!(parse_index(truncated).is_err());
+        let overlong = parse_index(&index_v4(&[0xff; 11])).unwrap_err();
+        assert_eq!(overlong.to_string(), "Invalid git index varint");
+        let overflow = parse_index(&index_v4(&[
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f,
+        ]));
+        assert_eq!(
+            overflow.unwrap_err().to_string(),
+            "Invalid git index varint"
+        );
+    }
+
+    #[test]
+    fn changes() {
+        let root = std::env::temp_dir().join(format!("gitnr-test-simulate-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join("dist/assets")).unwrap();
+        fs::create_dir_all(root.join("logs")).unwrap();
+        for file in [
+            "app.log",
+            "dist/app.js",
+            "dist/assets/app.css",
+            "logs/a.txt",
+            "keep.tmp",
+        ] {
+            fs::write(root.join(file), "").unwrap();
+        }
+        fs::write(root.join(".git/index"), index(&["dist/app.js", "keep.tmp"])).unwrap();
+
+        let simulation = Simulation::run(&root, "logs/\n*.tmp\n", "*.log\ndist/\n").unwrap();
+        assert_eq!(simulation.ignored, vec!["app.log", "dist/"]);
+        assert_eq!(simulation.unignored, vec!["logs/"]);
+        assert_eq!(simulation.tracked, vec!["dist/app.js"]);
+        assert_eq!(simulation.files, 2);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod search {
+    use crate::commands::search::state::collection::{UICollection, UICollectionItem};
+    use crate::template::collection::TemplateCollectionKind;
+    use crate::template::item::Template;
+    use crate::template::provider::provider;
+    use std::sync::{Arc, Mutex};
+
+    /// Builds a loaded collection list of the provider with the templates
+    fn collection(prefix: &str, names: &[&str]) -> UICollection {
+        let kind = TemplateCollectionKind::new(provider(prefix).unwrap(), prefix, prefix);
+        let items = names
+            .iter()
+            .map(|name| {
+                let template = Template::new(&format!("{prefix}:{name}")).unwrap();
+                UICollectionItem::new(&kind, &template).unwrap()
+            })
+            .collect();
+        let mut collection = UICollection::new(kind);
+        collection.set_items(items, "");
+        collection
+    }
+
+    fn names(collection: &UICollection) -> Vec<(String, String)> {
+        collection
+            .values
+            .iter()
+            .map(|item| (item.kind.provider.clone(), item.name.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn filter() {
+        let mut collection = collection("gh", &["Go", "Jekyll", "Java", "JetBrains", "Joomla"]);
+        collection.filter("jb");
+        assert_eq!(collection.values[0].name, "JetBrains");
+        assert_eq!(collection.values[0].matches, vec![0, 3]);
+
+        collection.filter("");
+        assert_eq!(collection.values.len(), 5);
+        assert!(collection.values.iter().all(|item| item.matches.is_empty()));
+    }
+
+    #[test]
+    fn merge() {
+        let collections = [
+            collection("gh", &["Rust", "Node"]),
+            collection("tt", &["rust", "Go", "node"]),
+            collection("ghc", &["Rust", "Go"]),
+        ]
+        .map(|c| Arc::new(Mutex::new(c)));
+
+        let mut all = UICollection::all();
+        all.set_items(UICollection::merge(&collections), "");
+        let pair = |p: &str, n: &str| (p.to_string(), n.to_string());
+        // Only the GitHub and TopTal duplicates are merged, other templates with the same name
+        // are distinct and stay listed
+        assert_eq!(
+            names(&all),
+            vec![
+                pair("gh:", "Rust"),
+                pair("gh:", "Node"),
+                pair("tt:", "Go"),
+                pair("ghc:", "Rust"),
+                pair("ghc:", "Go"),
+            ]
+        );
+    }
+
+    #[test]
+    fn ties() {
+        // Templates with the same score keep the collection order
+        let gh = collection("gh", &["Rust"]);
+        let tt = collection("tt", &["Rust"]);
+        let pair = |p: &str| (p.to_string(), "Rust".to_string());
+        for (first, second) in [(&gh, &tt), (&tt, &gh)] {
+            let items = [first, second]
+                .iter()
+                .flat_map(|c| c.items.clone())
+                .collect();
+            let mut list = UICollection::all();
+            list.set_items(items, "rus");
+            let expected = vec![pair(&first.kind.provider), pair(&second.kind.provider)];
+            assert_eq!(names(&list), expected);
+        }
+    }
+}
+
+#[cfg(test)]
+mod git {
+    use crate::util::git::{config_value, Repository};
+    use std::fs;
+
+    #[test]
+    fn config() {
+        let config = "[user]\n\tname = Me\n[Core]\n\texcludesFile = ~/a\n\
+            [core \"sub\"] autocrlf = true\n[core]\n\tExcludesFile = \"~/my ignore\" # note\n";
+        let value = config_value(config, "core", "excludesfile");
+        assert_eq!(value.as_deref(), Some("~/my ignore"));
+        assert_eq!(config_value(config, "core", "autocrlf"), None);
+        assert_eq!(config_value(config, "user", "excludesfile"), None);
+    }
+
+    #[test]
+    fn worktree() {
+        let root = std::env::temp_dir().join(format!("gitnr-test-git-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let git_dir = root.join("main/.git/worktrees/linked");
+        fs::create_dir_all(&git_dir).unwrap();
+        fs::create_dir_all(root.join("linked/src")).unwrap();
+        fs::write(git_dir.join("commondir"), "../..\n").unwrap();
+        fs::write(
+            root.join("linked/.git"),
+            format!("gitdir: {}\n", git_dir.display()),
+        )
+        .unwrap();
+
+        let repository = Repository::discover(&root.join("linked/src")).unwrap();
+        assert_eq!(repository.root, root.join("linked"));
+        let common_dir = root.join("main/.git").canonicalize().unwrap();
+        assert_eq!(repository.exclude_file(), common_dir.join("info/exclude"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn exclude_merge() {
+        use crate::cli::{Cli, Commands};
+        use crate::commands::create::{is_merge, write_output};
+        use clap::Parser;
+
+        let create = |args: &[&str]| match Cli::parse_from(args).command {
+            Some(Commands::Create(cmd)) => cmd,
+            _ => panic!("Expected the create command"),
+        };
+        assert!(is_merge(&create(&["gitnr", "create", "-e", "gh:Rust"])));
+        assert!(is_merge(&create(&["gitnr", "create", "-g", "gh:Rust"])));
+        assert!(is_merge(&create(&[
+            "gitnr", "create", "-s", "-m", "gh:Rust"
+        ])));
+        assert!(!is_merge(&create(&["gitnr", "create", "-s", "gh:Rust"])));
+
+        let root = std::env::temp_dir().join(format!("gitnr-test-exclude-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join(".git/info")).unwrap();
+        let exclude = Repository::discover(&root).unwrap().exclude_file();
+        fs::write(&exclude, "# My rules\n*.local\n").unwrap();
+
+        // Content outside of the managed block is kept when writing to the file again
+        let merge = is_merge(&create(&["gitnr", "create", "-e", "gh:Rust"]));
+        write_output(&exclude, "/target", merge).unwrap();
+        write_output(&exclude, "/dist", merge).unwrap();
+        let content = fs::read_to_string(&exclude).unwrap();
+        assert!(content.starts_with("# My rules\n*.local\n"));
+        assert!(content.contains("/dist\n"));
+        assert!(!content.contains("/target"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
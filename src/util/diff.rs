@@ -0,0 +1,89 @@
+use similar::{ChangeTag, TextDiff};
+use yansi::Paint;
+
+/// A line of a unified diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// The file headers, e.g. `--- .gitignore`
+    OldHeader(String),
+    NewHeader(String),
+    /// The line ranges of a group of changes, e.g. `@@ -1,3 +1,4 @@`
+    Hunk(String),
+    Delete(String),
+    Insert(String),
+    Equal(String),
+}
+
+impl DiffLine {
+    /// Returns the line as it's printed in a unified diff
+    pub fn text(&self) -> String {
+        match self {
+            DiffLine::OldHeader(header) => format!("--- {header}"),
+            DiffLine::NewHeader(header) => format!("+++ {header}"),
+            DiffLine::Hunk(hunk) => hunk.to_string(),
+            DiffLine::Delete(line) => format!("-{line}"),
+            DiffLine::Insert(line) => format!("+{line}"),
+            DiffLine::Equal(line) => format!(" {line}"),
+        }
+    }
+}
+
+/// Returns the lines of a unified diff between the old and new content, which is empty if
+/// the content is the same
+pub fn diff_lines(old: &str, new: &str, old_header: &str, new_header: &str) -> Vec<DiffLine> {
+    let diff = TextDiff::from_lines(old, new);
+    let mut lines = Vec::new();
+
+    for group in diff.grouped_ops(3) {
+        if lines.is_empty() {
+            lines.push(DiffLine::OldHeader(old_header.to_string()));
+            lines.push(DiffLine::NewHeader(new_header.to_string()));
+        }
+
+        let first = group.first().unwrap();
+        let last = group.last().unwrap();
+        let old_range = first.old_range().start..last.old_range().end;
+        let new_range = first.new_range().start..last.new_range().end;
+        lines.push(DiffLine::Hunk(format!(
+            "@@ -{},{} +{},{} @@",
+            old_range.start + 1,
+            old_range.len(),
+            new_range.start + 1,
+            new_range.len()
+        )));
+
+        for op in group.iter() {
+            for change in diff.iter_changes(op) {
+                let line = change.to_string_lossy();
+                let line = line.trim_end_matches(['\n', '\r']).to_string();
+                lines.push(match change.tag() {
+                    ChangeTag::Delete => DiffLine::Delete(line),
+                    ChangeTag::Insert => DiffLine::Insert(line),
+                    ChangeTag::Equal => DiffLine::Equal(line),
+                });
+            }
+        }
+    }
+
+    lines
+}
+
+/// Returns a colored unified diff between the old and new content
+pub fn unified_diff(old: &str, new: &str, old_header: &str, new_header: &str) -> String {
+    let mut output = String::new();
+    for line in diff_lines(old, new, old_header, new_header) {
+        let text = line.text();
+        let text = match line {
+            DiffLine::OldHeader(_) => text.red().bold().to_string(),
+            DiffLine::NewHeader(_) => text.green().bold().to_string(),
+            DiffLine::Hunk(_) => text.cyan().to_string(),
+            DiffLine::Delete(_) => text.red().to_string(),
+            DiffLine::Insert(_) => text.green().to_string(),
+            DiffLine::Equal(_) => text.dim().to_string(),
+        };
+        output.push_str(&text);
+        output.push('\n');
+    }
+
+    output
+}
@@ -1,14 +1,19 @@
 use crate::cli::get_cli;
+use crate::config::settings::{CacheKind, Settings};
+use crate::util::http::is_network_error;
+use crate::util::notes::note;
 use crate::util::package;
 use anyhow::Context;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use once_cell::sync::Lazy;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::collections::HashMap;
-use std::fs;
+use std::fs::{self, File};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
+use std::time::SystemTime;
 
 /// Reads a JSON file and parses it into the given type
 pub fn read_json_file<T: DeserializeOwned>(filepath: &str) -> Result<T> {
@@ -19,27 +24,113 @@ pub fn read_json_file<T: DeserializeOwned>(filepath: &str) -> Result<T> {
     Ok(data)
 }
 
-/// Writes the given data to a JSON file
+/// Checks if reading a JSON file failed because its content couldn't be parsed, rather than
+/// because the file couldn't be read
+pub fn is_parse_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|e| e.is::<serde_json::Error>())
+}
+
+/// Writes the given data to a JSON file.
+///
+/// The data is written to a temporary file which then replaces the file, so other processes
+/// never read a partially written file, while holding the file lock so concurrent writes
+/// don't interleave.
 pub fn write_json_file<T: Serialize>(filepath: &str, data: &T) -> Result<()> {
+    let _lock = lock_file(filepath)?;
+    replace_json_file(filepath, data)
+}
+
+/// Updates a JSON file with the data returned by the `update` function, which is given the
+/// current file data if it can be read. The file lock is held from reading to writing the
+/// file so no changes made by other processes in between are lost.
+pub fn update_json_file<T: Serialize + DeserializeOwned>(
+    filepath: &str,
+    update: impl FnOnce(Option<T>) -> T,
+) -> Result<()> {
+    let _lock = lock_file(filepath)?;
+    let current = cache_exists(filepath)
+        .then(|| read_json_file(filepath).ok())
+        .flatten();
+    replace_json_file(filepath, &update(current))
+}
+
+/// Atomically replaces the file with the data written to a temporary file
+fn replace_json_file<T: Serialize>(filepath: &str, data: &T) -> Result<()> {
     let cache_json = serde_json::to_string(data)?;
-    fs::write(filepath, cache_json)
+    let tmp_path = format!("{filepath}.tmp-{}", std::process::id());
+    fs::write(&tmp_path, cache_json)
+        .and_then(|_| fs::rename(&tmp_path, filepath))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&tmp_path);
+        })
         .with_context(|| format!("Failed to write JSON data to file: {filepath}"))?;
     Ok(())
 }
 
+/// Takes the advisory lock shared by all gitnr processes writing to the file, which is
+/// released when the returned lock file is dropped
+fn lock_file(filepath: &str) -> Result<File> {
+    let path = format!("{filepath}{LOCK_FILE_SUFFIX}");
+    if let Some(dir) = Path::new(filepath).parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory for lock file\n{path}"))?;
+    }
+    let file = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open lock file\n{path}"))?;
+    file.lock()
+        .with_context(|| format!("Failed to take the lock of lock file\n{path}"))?;
+    Ok(file)
+}
+
+/// Moves a cache file that can't be parsed aside so it can be inspected, warning that the
+/// cache was reset
+pub fn quarantine_file(filepath: &str) {
+    let quarantined = format!("{filepath}.corrupt");
+    match fs::rename(filepath, &quarantined) {
+        Ok(_) => note(&format!(
+            "Cache file was corrupt and has been moved to {quarantined}, starting with an empty cache"
+        )),
+        Err(_) => note(&format!(
+            "Cache file {filepath} was corrupt, starting with an empty cache"
+        )),
+    }
+}
+
+/// Suffix of the file locked while writing to the file of the same name
+const LOCK_FILE_SUFFIX: &str = ".lock";
+
 static CACHE_RECHECKED: Lazy<Mutex<HashMap<String, bool>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
-/// Returns the cache filepath for the given name
-pub fn cache_filepath(name: &str) -> String {
-    let cache_dir: PathBuf = match dirs::cache_dir() {
+/// When enabled, cached content is used regardless of its age and nothing is fetched
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// When enabled, offline mode is enabled for the rest of the run once the network is unreachable
+static AUTO_OFFLINE: AtomicBool = AtomicBool::new(true);
+
+/// Returns the directory all the cache files are stored in
+pub fn cache_dir() -> PathBuf {
+    // Tests use their own cache instead of the user's cache
+    if cfg!(test) {
+        return std::env::temp_dir().join(format!("gitnr-test-cache-{}", std::process::id()));
+    }
+    match dirs::cache_dir() {
         Some(dir) => dir.join(package::NAME.to_lowercase()),
         None => std::env::current_dir()
             .unwrap()
             .join(format!(".{}", package::NAME.to_lowercase())),
-    };
+    }
+}
 
-    let cache_path = name.split('/').fold(cache_dir, |acc, part| acc.join(part));
+/// Returns the cache filepath for the given name
+pub fn cache_filepath(name: &str) -> String {
+    let cache_path = name
+        .split('/')
+        .fold(cache_dir(), |acc, part| acc.join(part));
 
     fs::create_dir_all(cache_path.parent().unwrap()).unwrap();
     cache_path.to_str().unwrap().to_string()
@@ -60,3 +151,59 @@ pub fn cache_is_invalidated(key: &str) -> bool {
     }
     false
 }
+
+/// Checks if content cached for the provider with the given prefix at the `updated` time is
+/// still within its configured TTL
+pub fn cache_is_fresh(updated: SystemTime, prefix: &str, kind: CacheKind) -> Result<bool> {
+    let ttl = Settings::get()?.cache.ttl(prefix, kind);
+    Ok(!ttl.is_expired(updated))
+}
+
+/// Enables or disables offline mode
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Checks if offline mode is enabled, meaning only cached content can be used
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Enables or disables switching to offline mode when the network is unreachable. It's
+/// disabled by the terminal UI, where failed fetches can be retried once back online.
+pub fn set_auto_offline(enabled: bool) {
+    AUTO_OFFLINE.store(enabled, Ordering::Relaxed);
+}
+
+/// Fetches content with the `fetch` function unless offline, falling back to the `stale`
+/// cached content when offline or when the network is unreachable, which also enables offline
+/// mode for the rest of the run to prevent waiting on every other request (see
+/// [`set_auto_offline`]).
+pub fn fetch_or_stale<T>(
+    description: &str,
+    stale: Option<T>,
+    fetch: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    if !is_offline() {
+        match fetch() {
+            Ok(content) => return Ok(content),
+            Err(e) if !is_network_error(&e) => return Err(e),
+            Err(e) => {
+                if AUTO_OFFLINE.load(Ordering::Relaxed) {
+                    set_offline(true);
+                    note("Network is unreachable, continuing offline");
+                }
+                if stale.is_none() {
+                    return Err(e);
+                }
+            }
+        }
+    }
+    match stale {
+        Some(content) => {
+            note(&format!("Using outdated cached {description}"));
+            Ok(content)
+        }
+        None => bail!("The {description} is not cached and cannot be fetched while offline"),
+    }
+}
@@ -0,0 +1,273 @@
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+/// Size of the fixed-width part of an index entry, up to and including the flags
+const ENTRY_HEADER_SIZE: usize = 62;
+
+/// A git repository found on disk
+#[derive(Debug)]
+pub struct Repository {
+    /// The root directory of the working tree
+    pub root: PathBuf,
+    /// The git directory, usually `.git` in the root or the one a `.git` file points to
+    pub git_dir: PathBuf,
+}
+
+impl Repository {
+    /// Finds the repository containing the path by searching it and its parent directories
+    pub fn discover(path: &Path) -> Option<Self> {
+        path.ancestors().find_map(|dir| {
+            let dot_git = dir.join(".git");
+            let git_dir = if dot_git.is_dir() {
+                dot_git
+            } else if dot_git.is_file() {
+                // Worktrees and submodules use a file pointing to the actual git directory
+                let content = fs::read_to_string(&dot_git).ok()?;
+                let target = content.trim().strip_prefix("gitdir:")?.trim();
+                dir.join(target)
+            } else {
+                return None;
+            };
+            Some(Self {
+                root: dir.to_path_buf(),
+                git_dir,
+            })
+        })
+    }
+
+    /// Returns the directory shared by all worktrees of the repository, which holds the
+    /// repository wide files such as `info/exclude`
+    pub fn common_dir(&self) -> PathBuf {
+        // Linked worktrees point to the shared directory of the main worktree
+        match fs::read_to_string(self.git_dir.join("commondir")) {
+            Ok(content) => {
+                let dir = self.git_dir.join(content.trim());
+                dir.canonicalize().unwrap_or(dir)
+            }
+            Err(_) => self.git_dir.clone(),
+        }
+    }
+
+    /// Returns the path of the repository exclude file (`.git/info/exclude`)
+    pub fn exclude_file(&self) -> PathBuf {
+        self.common_dir().join("info").join("exclude")
+    }
+
+    /// Returns the paths of the files tracked in the index, relative to the repository root
+    /// and using '/' as the separator
+    pub fn tracked_files(&self) -> Result<Vec<String>> {
+        let path = self.git_dir.join("index");
+        if !path.is_file() {
+            // A new repository has no index until something is staged
+            return Ok(Vec::new());
+        }
+        let data = fs::read(&path).with_context(|| {
+            format!(
+                "Failed to read git index at path\n{}",
+                path.to_str().unwrap_or("...unknown path")
+            )
+        })?;
+        parse_index(&data).with_context(|| {
+            format!(
+                "Failed to parse git index at path\n{}",
+                path.to_str().unwrap_or("...unknown path")
+            )
+        })
+    }
+}
+
+/// Returns the path of the global excludes file, which is the `core.excludesFile` setting of
+/// the user's git config or `$XDG_CONFIG_HOME/git/ignore` by default
+pub fn global_excludes_file() -> Result<PathBuf> {
+    let home = dirs::home_dir().with_context(|| "Failed to get the home directory")?;
+    let xdg_config = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => home.join(".config"),
+    };
+
+    // Git reads the XDG config before ~/.gitconfig, so the latter takes precedence
+    let config_files = match env::var_os("GIT_CONFIG_GLOBAL") {
+        Some(path) => vec![PathBuf::from(path)],
+        None => vec![
+            xdg_config.join("git").join("config"),
+            home.join(".gitconfig"),
+        ],
+    };
+    let mut excludes_file: Option<String> = None;
+    for path in config_files.iter().filter(|p| p.is_file()) {
+        let content = fs::read_to_string(path).with_context(|| {
+            format!(
+                "Failed to read git config at path\n{}",
+                path.to_str().unwrap_or("...unknown path")
+            )
+        })?;
+        if let Some(value) = config_value(&content, "core", "excludesfile") {
+            excludes_file = Some(value);
+        }
+    }
+
+    Ok(match excludes_file {
+        Some(path) => match path.strip_prefix("~/") {
+            Some(path) => home.join(path),
+            None => PathBuf::from(path),
+        },
+        None => xdg_config.join("git").join("ignore"),
+    })
+}
+
+/// Returns the last value of a key in a git config file. Section and key names are case
+/// insensitive, keys in subsections (e.g. `[core "sub"]`) are skipped.
+pub fn config_value(content: &str, section: &str, key: &str) -> Option<String> {
+    let mut current = String::new();
+    let mut value: Option<String> = None;
+
+    for line in content.lines() {
+        let mut line = line.trim();
+        if let Some(header) = line.strip_prefix('[') {
+            let name = header.split(']').next().unwrap_or_default();
+            current = name.trim().to_lowercase();
+            // Variables can follow the section header on the same line
+            line = header
+                .split_once(']')
+                .map(|(_, rest)| rest)
+                .unwrap_or_default();
+        }
+        if current == section {
+            value = config_entry(line, key).or(value);
+        }
+    }
+
+    value
+}
+
+/// Parses a `key = value` config line, returning the value if it's for the given key
+fn config_entry(line: &str, key: &str) -> Option<String> {
+    let (name, raw) = line.split_once('=')?;
+    if !name.trim().eq_ignore_ascii_case(key) {
+        return None;
+    }
+
+    // Unquote the value, stopping at a comment outside of quotes
+    let mut value = String::new();
+    let mut quoted = false;
+    let mut chars = raw.trim().chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted = !quoted,
+            '\\' => match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some(c) => value.push(c),
+                None => break,
+            },
+            '#' | ';' if !quoted => break,
+            c => value.push(c),
+        }
+    }
+    Some(value.trim_end().to_string())
+}
+
+/// Parses the paths of the entries in a git index file (versions 2 to 4)
+pub fn parse_index(data: &[u8]) -> Result<Vec<String>> {
+    if data.len() < 12 || &data[..4] != b"DIRC" {
+        bail!("Invalid git index signature")
+    }
+    let version = read_u32(data, 4)?;
+    if !(2..=4).contains(&version) {
+        bail!("Unsupported git index version: {version}")
+    }
+    let count = read_u32(data, 8)? as usize;
+
+    let mut paths: Vec<String> = Vec::with_capacity(count);
+    let mut previous: Vec<u8> = Vec::new();
+    let mut offset = 12;
+    for _ in 0..count {
+        let start = offset;
+        let flags = read_u16(data, start + ENTRY_HEADER_SIZE - 2)?;
+        offset += ENTRY_HEADER_SIZE;
+        if version >= 3 && flags & 0x4000 != 0 {
+            offset += 2;
+        }
+
+        let path = if version == 4 {
+            // Paths are prefix compressed against the previous entry and not padded
+            let (strip, read) = read_varint(data, offset)?;
+            offset += read;
+            let end = find_nul(data, offset)?;
+            let keep = previous.len().checked_sub(strip as usize);
+            let keep = keep.with_context(|| "Invalid git index path compression")?;
+            let mut path = previous[..keep].to_vec();
+            path.extend_from_slice(&data[offset..end]);
+            offset = end + 1;
+            path
+        } else {
+            // Entries are padded with 1-8 NUL bytes to a multiple of 8 bytes
+            let end = find_nul(data, offset)?;
+            let path = data[offset..end].to_vec();
+            offset = start + ((end - start + 8) & !7);
+            path
+        };
+
+        // Entries with unmerged changes are listed once per stage
+        let path_str = String::from_utf8_lossy(&path).into_owned();
+        if paths.last() != Some(&path_str) {
+            paths.push(path_str);
+        }
+        previous = path;
+    }
+
+    Ok(paths)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    match data.get(offset..offset + 4) {
+        Some(bytes) => Ok(u32::from_be_bytes(bytes.try_into()?)),
+        None => bail!("Unexpected end of git index"),
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    match data.get(offset..offset + 2) {
+        Some(bytes) => Ok(u16::from_be_bytes(bytes.try_into()?)),
+        None => bail!("Unexpected end of git index"),
+    }
+}
+
+/// Reads the variable width integer git uses for offsets, returning it with the bytes read
+fn read_varint(data: &[u8], offset: usize) -> Result<(u64, usize)> {
+    let mut read = 0;
+    let mut value = 0;
+    loop {
+        // A u64 never takes more than 10 bytes
+        if read == 10 {
+            bail!("Invalid git index varint")
+        }
+        let byte = *data
+            .get(offset + read)
+            .with_context(|| "Unexpected end of git index")?;
+        let low = (byte & 0x7f) as u64;
+        value = if read == 0 {
+            low
+        } else {
+            // Each continuation adds one before shifting, so no value has two encodings
+            value
+                .checked_add(1)
+                .filter(|v| v.leading_zeros() >= 7)
+                .and_then(|v| v.checked_shl(7))
+                .with_context(|| "Invalid git index varint")?
+                | low
+        };
+        read += 1;
+        if byte & 0x80 == 0 {
+            return Ok((value, read));
+        }
+    }
+}
+
+fn find_nul(data: &[u8], offset: usize) -> Result<usize> {
+    data.get(offset..)
+        .and_then(|rest| rest.iter().position(|b| *b == 0))
+        .map(|i| offset + i)
+        .with_context(|| "Unexpected end of git index")
+}
@@ -1,7 +1,9 @@
+use crate::config::credentials::token_for;
 use anyhow::Context;
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use ureq::{Agent, AgentBuilder};
+use ureq::{Agent, AgentBuilder, ErrorKind, Request, Response};
 
 static AGENT: Lazy<Agent> = Lazy::new(|| {
     AgentBuilder::new()
@@ -16,3 +18,75 @@ static AGENT: Lazy<Agent> = Lazy::new(|| {
 pub fn http() -> Agent {
     AGENT.clone()
 }
+
+/// Creates a GET request, authenticated with the access token configured for the URL host
+pub fn http_get(url: &str) -> Request {
+    let request = http().get(url);
+    match token_for(url) {
+        Some(token) => request.set("Authorization", &format!("Bearer {}", token.secret())),
+        None => request,
+    }
+}
+
+/// Checks if the error was caused by the network being unreachable, rather than by the response
+pub fn is_network_error(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .any(|e| match e.downcast_ref::<ureq::Error>() {
+            Some(ureq::Error::Transport(t)) => matches!(
+                t.kind(),
+                ErrorKind::Dns | ErrorKind::ConnectionFailed | ErrorKind::Io
+            ),
+            _ => false,
+        })
+}
+
+/// The `ETag` and `Last-Modified` headers of a cached response, sent back to the server to
+/// check if the cached content is still current without downloading it again
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    fn from_response(res: &Response) -> Self {
+        Self {
+            etag: res.header("ETag").map(String::from),
+            last_modified: res.header("Last-Modified").map(String::from),
+        }
+    }
+}
+
+/// Result of a request revalidating cached content
+#[derive(Debug)]
+pub enum Revalidated<T> {
+    /// The cached content is still current (HTTP 304)
+    NotModified,
+    /// The content has changed, or wasn't cached, along with its new validators
+    Modified(T, Validators),
+}
+
+/// Sends the request with the `If-None-Match` and `If-Modified-Since` headers of the cached
+/// response validators, if any
+pub fn call_revalidate(
+    request: Request,
+    cached: Option<&Validators>,
+) -> anyhow::Result<Revalidated<Response>> {
+    let mut request = request;
+    if let Some(cached) = cached {
+        if let Some(etag) = &cached.etag {
+            request = request.set("If-None-Match", etag);
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            request = request.set("If-Modified-Since", last_modified);
+        }
+    }
+    let res = request.call()?;
+    if res.status() == 304 {
+        return Ok(Revalidated::NotModified);
+    }
+    let validators = Validators::from_response(&res);
+    Ok(Revalidated::Modified(res, validators))
+}
@@ -1,4 +1,9 @@
+pub mod diff;
 pub mod fs;
+pub mod git;
 pub mod http;
+pub mod notes;
 pub mod package;
+pub mod parallel;
 pub mod string;
+pub mod time;
@@ -0,0 +1,18 @@
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+/// Notes about how the output was produced, e.g. when outdated cached content was used
+static NOTES: Lazy<Mutex<Vec<String>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Adds a note to print once the command finishes, ignoring duplicates
+pub fn note(message: &str) {
+    let mut notes = NOTES.lock().unwrap();
+    if !notes.iter().any(|n| n == message) {
+        notes.push(message.to_string());
+    }
+}
+
+/// Takes the notes added so far
+pub fn take_notes() -> Vec<String> {
+    std::mem::take(&mut *NOTES.lock().unwrap())
+}
@@ -1,4 +1,23 @@
-use std::collections::HashSet;
+use indoc::formatdoc;
+
+/// Returns a comment banner used to title sections of an ignore file
+pub fn banner(title: &str) -> String {
+    let title = format!("###  {title}  ###");
+    let seperator = "-".repeat(title.len() - 6);
+    formatdoc! {"
+        ###{seperator}###
+        {title}
+        ###{seperator}###
+    ", seperator = seperator, title = title}
+}
+
+/// Returns the title of a banner title line created with `banner`
+pub fn banner_title(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix("###  ")?
+        .strip_suffix("  ###")
+        .map(str::trim)
+}
 
 /// Strips the given prefixes from the input string
 pub fn strip_prefixes<'a>(input: &'a str, prefixes: &[&str]) -> Option<&'a str> {
@@ -16,34 +35,40 @@ pub fn strip_suffixes<'a>(input: &'a str, suffixes: &[&str]) -> Option<&'a str>
         .and_then(|&prefix| input.strip_suffix(prefix))
 }
 
-/// Trims duplicate lines from the given strings
-pub fn trim_duplicate_lines(strings: Vec<String>) -> Vec<String> {
-    let mut unique_lines: HashSet<&str> = HashSet::new();
-    let mut result: Vec<String> = Vec::new();
+/// Matches the text against a pattern where `*` matches any characters and `?` matches one
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
 
-    for string in strings.iter() {
-        let unique_lines_in_string: Vec<_> = string
-            .lines()
-            .filter(|&line| {
-                // Always include empty lines
-                if line.trim().is_empty() {
-                    true
-                } else {
-                    // Include non-empty line only if it's unique
-                    unique_lines.insert(line)
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            // Retry from the last wildcard, letting it match one more character
+            _ => match backtrack {
+                Some((bp, bt)) => {
+                    p = bp + 1;
+                    t = bt + 1;
+                    backtrack = Some((bp, bt + 1));
                 }
-            })
-            .collect();
-        let unique_lines = unique_lines_in_string.join("\n");
-        let unique_lines = replace_multiple_empty_lines(&unique_lines);
-        result.push(unique_lines);
+                None => return false,
+            },
+        }
     }
 
-    result
+    pattern[p..].iter().all(|&c| c == '*')
 }
 
 /// Replaces sequential empty lines with a single empty line
-fn replace_multiple_empty_lines(input: &str) -> String {
+pub fn replace_multiple_empty_lines(input: &str) -> String {
     let mut result = String::new();
     let mut was_previous_line_empty = false;
 
@@ -64,3 +89,12 @@ fn replace_multiple_empty_lines(input: &str) -> String {
 
     result
 }
+
+/// Formats a size in bytes using the largest binary unit, e.g. "1.5 KiB"
+pub fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1024 * 1024 => format!("{:.1} MiB", b as f64 / (1024.0 * 1024.0)),
+        b if b >= 1024 => format!("{:.1} KiB", b as f64 / 1024.0),
+        b => format!("{b} B"),
+    }
+}
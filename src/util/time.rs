@@ -0,0 +1,79 @@
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::time::{Duration, SystemTime};
+
+/// How long cached content stays fresh before it's fetched again
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Ttl {
+    /// The cached content never expires and is only fetched again on refresh
+    Never,
+    /// The cached content expires after the duration
+    After(Duration),
+}
+
+impl Ttl {
+    /// Parses a TTL which is either a duration such as "12h" or "never"
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim() {
+            "never" => Ok(Self::Never),
+            value => Ok(Self::After(parse_duration(value)?)),
+        }
+    }
+
+    /// Checks if content updated at the given time has expired
+    pub fn is_expired(&self, updated: SystemTime) -> bool {
+        match self {
+            Self::Never => false,
+            Self::After(ttl) => age(updated) > *ttl,
+        }
+    }
+}
+
+impl TryFrom<String> for Ttl {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::parse(&value)
+    }
+}
+
+/// Parses a duration such as "30s", "15m", "12h", "7d" or "2w"
+pub fn parse_duration(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount.parse().with_context(|| {
+        format!("Invalid duration: '{value}'. Expected a number and unit, e.g. 12h or 7d")
+    })?;
+    let seconds = match unit.trim() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        "w" => 60 * 60 * 24 * 7,
+        _ => bail!("Invalid duration unit in '{value}'. Expected one of s, m, h, d or w"),
+    };
+    let Some(total) = amount.checked_mul(seconds) else {
+        bail!("Invalid duration: '{value}'. The duration is too large");
+    };
+    Ok(Duration::from_secs(total))
+}
+
+/// Formats a duration in its largest whole unit, e.g. "3d" or "45m"
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds {
+        s if s >= 60 * 60 * 24 => format!("{}d", s / (60 * 60 * 24)),
+        s if s >= 60 * 60 => format!("{}h", s / (60 * 60)),
+        s if s >= 60 => format!("{}m", s / 60),
+        s => format!("{s}s"),
+    }
+}
+
+/// Returns how long ago the time was, zero if it's in the future
+pub fn age(time: SystemTime) -> Duration {
+    time.elapsed().unwrap_or_default()
+}